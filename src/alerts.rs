@@ -0,0 +1,91 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
+
+use crate::{config::Config, info::SystemInfo, sort, util};
+
+// remembers which thresholds are currently breached, so each breach is only reported once
+pub struct Alerts {
+    breached: HashSet<String>,
+    // when each breach was last reported, a threshold flapping around its limit stays quiet
+    last_reported: HashMap<String, Instant>,
+}
+
+impl Alerts {
+    pub fn new() -> Alerts {
+        Alerts {
+            breached: HashSet::new(),
+            last_reported: HashMap::new(),
+        }
+    }
+
+    // the messages for thresholds that were breached since the last check
+    pub fn check(&mut self, sys: &SystemInfo, config: &Config) -> Vec<String> {
+        let mut current = vec![];
+
+        if let Some(level) = config.alert_memory_pressure {
+            let pressure = sys.memory_pressure_level();
+
+            if pressure >= level {
+                current.push((
+                    "memory".to_string(),
+                    format!("memory pressure is {}", pressure.description()),
+                ));
+            }
+        }
+
+        if let Some(budget) = config.alert_process_memory {
+            for process in sort::merge_threads(&sys.processes) {
+                if process.ram > budget {
+                    current.push((
+                        format!("process:{}", process.pid),
+                        format!(
+                            "{} ({}) is using {}",
+                            process.name,
+                            process.pid,
+                            util::format_bytes(process.ram)
+                        ),
+                    ));
+                }
+            }
+        }
+
+        if let Some(limit) = config.alert_disk_percent {
+            for disk in &sys.usage.disks {
+                if disk.percent >= limit {
+                    current.push((
+                        format!("disk:{}", disk.mount),
+                        format!("{} is {:.0}% full", disk.mount, disk.percent),
+                    ));
+                }
+
+                // new files fail to be created just the same when the inodes run out
+                if let Some(percent) = disk.inode_percent().filter(|p| *p >= limit) {
+                    current.push((
+                        format!("inodes:{}", disk.mount),
+                        format!("{} has used {:.0}% of its inodes", disk.mount, percent),
+                    ));
+                }
+            }
+        }
+
+        let mut messages = vec![];
+
+        for (key, message) in &current {
+            let quiet = self
+                .last_reported
+                .get(key)
+                .is_some_and(|t| t.elapsed() < config.alert_interval);
+
+            if !self.breached.contains(key) && !quiet {
+                self.last_reported.insert(key.clone(), Instant::now());
+                messages.push(message.clone());
+            }
+        }
+
+        self.breached = current.into_iter().map(|(key, _)| key).collect();
+
+        messages
+    }
+}
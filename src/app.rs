@@ -0,0 +1,782 @@
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use ratatui::layout::Rect;
+
+use crate::{
+    columns::{Column, ColumnLayout},
+    connections::{self, Connection},
+    history::{ClockHistory, NetworkHistory},
+    labels::{ClockStat, LabelMode, SizeUnit},
+    procfs::{self, IoPriority, ProcessExtras},
+    info::{BaselineReport, ProcessData, ProcessState, Subsystem, SystemInfo},
+    sort::{self, Aggregation, DiskSorter, ProcessGroup, ProcessSorter},
+    theme::Theme,
+};
+
+const STATUS_DURATION: Duration = Duration::from_secs(3);
+// a second ctrl-c within this long quits
+const QUIT_CONFIRM_WINDOW: Duration = Duration::from_secs(1);
+// a core climbing past this scrolls the cpu panel to it
+const CORE_SPIKE_PERCENT: f32 = 90.0;
+
+// which panel the arrow keys act on, switched with tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Panel {
+    Cpu,
+    // specs and network share a panel
+    Specs,
+    Processes,
+}
+
+// trades precision for pidwatch's own cpu use, switched with 1, 2 and 3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerformanceMode {
+    Low,
+    Normal,
+    High,
+}
+
+impl PerformanceMode {
+    pub fn parse(value: &str) -> Result<PerformanceMode, String> {
+        match value {
+            "low" => Ok(PerformanceMode::Low),
+            "normal" => Ok(PerformanceMode::Normal),
+            "high" => Ok(PerformanceMode::High),
+            _ => Err(format!(
+                "unknown performance mode '{}', expected low, normal or high",
+                value
+            )),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PerformanceMode::Low => "low",
+            PerformanceMode::Normal => "normal",
+            PerformanceMode::High => "high",
+        }
+    }
+
+    // normal keeps whatever the config asks for
+    pub fn refresh_interval(self, configured: Duration) -> Duration {
+        match self {
+            PerformanceMode::Low => configured.max(Duration::from_secs(5)),
+            PerformanceMode::Normal => configured,
+            // sysinfo can't measure cpu usage over anything shorter than its minimum
+            PerformanceMode::High => configured
+                .min(Duration::from_millis(100))
+                .max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL),
+        }
+    }
+
+    pub fn frame_interval(self) -> Duration {
+        match self {
+            PerformanceMode::Low => Duration::from_millis(500),
+            PerformanceMode::Normal => Duration::from_millis(33),
+            PerformanceMode::High => Duration::from_millis(16),
+        }
+    }
+
+    // disks and network details aren't refreshed at all in low mode
+    pub fn skips_expensive(self) -> bool {
+        self == PerformanceMode::Low
+    }
+}
+
+// highlights matching rows in place, unlike a filter nothing is hidden
+pub struct Search {
+    pub query: String,
+    // still being typed, keys go into the query rather than to the usual bindings
+    pub typing: bool,
+}
+
+// a note for a process being typed, keys go into it until enter or esc
+pub struct NoteEdit {
+    pub pid: u32,
+    pub text: String,
+}
+
+// the io priority popup over the detail view, opened with i, applied with enter
+pub struct IoniceEdit {
+    pub pid: u32,
+    pub priority: IoPriority,
+}
+
+// how the state column shows a process's state, cycled with T
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateDisplay {
+    // R, S, Z... as ps shows them
+    Code,
+    Word,
+    // whatever sysinfo reported, before it's normalised
+    Raw,
+}
+
+impl StateDisplay {
+    pub fn parse(value: &str) -> Result<StateDisplay, String> {
+        match value {
+            "code" => Ok(StateDisplay::Code),
+            "word" => Ok(StateDisplay::Word),
+            "raw" => Ok(StateDisplay::Raw),
+            _ => Err(format!(
+                "unknown state display '{}', expected code, word or raw",
+                value
+            )),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StateDisplay::Code => "code",
+            StateDisplay::Word => "word",
+            StateDisplay::Raw => "raw",
+        }
+    }
+
+    // None hides the column again
+    pub fn next(display: Option<StateDisplay>) -> Option<StateDisplay> {
+        match display {
+            None => Some(StateDisplay::Code),
+            Some(StateDisplay::Code) => Some(StateDisplay::Word),
+            Some(StateDisplay::Word) => Some(StateDisplay::Raw),
+            Some(StateDisplay::Raw) => None,
+        }
+    }
+}
+
+// the process table's column edit mode, keys go to the selected column until it's left
+pub struct ColumnEdit {
+    pub selected: Column,
+    // put back if the edit is cancelled
+    pub original: ColumnLayout,
+}
+
+// the pages of the detail view, switched with left and right
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailTab {
+    Info,
+    Memory,
+    Io,
+    Network,
+    Environment,
+    Syscalls,
+}
+
+impl DetailTab {
+    pub const ALL: [DetailTab; 6] = [
+        DetailTab::Info,
+        DetailTab::Memory,
+        DetailTab::Io,
+        DetailTab::Network,
+        DetailTab::Environment,
+        DetailTab::Syscalls,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DetailTab::Info => "Info",
+            DetailTab::Memory => "Memory",
+            DetailTab::Io => "I/O",
+            DetailTab::Network => "Network",
+            DetailTab::Environment => "Environment",
+            DetailTab::Syscalls => "Syscalls",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        DetailTab::ALL.iter().position(|t| *t == self).unwrap_or_default()
+    }
+
+    // wraps around at either end
+    pub fn step(self, forward: bool) -> DetailTab {
+        let count = DetailTab::ALL.len();
+        let offset = if forward { 1 } else { count - 1 };
+
+        DetailTab::ALL[(self.index() + offset) % count]
+    }
+}
+
+// the detail view of one process, opened with enter
+pub struct ProcessDetail {
+    pub pid: u32,
+    // every process in the row it was opened from, the busiest first and the one shown
+    pub group: Vec<u32>,
+    pub tab: DetailTab,
+    // read on full refreshes only, walking every fd is too slow for each frame
+    pub connections: Vec<Connection>,
+    pub extras: ProcessExtras,
+    pub strace: Option<PathBuf>,
+}
+
+impl ProcessDetail {
+    pub fn new(group: Vec<u32>) -> ProcessDetail {
+        let pid = group.first().copied().unwrap_or_default();
+
+        ProcessDetail {
+            pid,
+            group,
+            tab: DetailTab::Info,
+            connections: connections::process_connections(pid),
+            extras: procfs::process_extras(pid),
+            strace: procfs::find_strace(),
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        self.connections = connections::process_connections(self.pid);
+        self.extras = procfs::process_extras(self.pid);
+    }
+}
+
+// the parts of the view that are kept between runs, see session.rs. a [columns] section
+// in the config still takes precedence. the theme isn't kept, it follows the terminal
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersistentAppState {
+    pub sorter: ProcessSorter,
+    pub sort_reversed: bool,
+    pub aggregation: Aggregation,
+    pub relative_cpu: bool,
+    pub merge_threads: bool,
+    pub show_nice: bool,
+    pub show_sched_policy: bool,
+    pub show_growth: bool,
+    pub show_connection_count: bool,
+    pub state_display: Option<StateDisplay>,
+    pub disk_sorter: DiskSorter,
+    pub labels: LabelMode,
+    pub units: SizeUnit,
+    pub columns: ColumnLayout,
+    pub performance_mode: PerformanceMode,
+}
+
+impl Default for PersistentAppState {
+    fn default() -> PersistentAppState {
+        AppState::new().persistent()
+    }
+}
+
+// where the view is scrolled to and what the ui measured on the last frame, none of it is
+// saved and every run starts from the defaults
+pub struct TransientAppState {
+    // set when the render closure panics, the next frames show it instead of the panels
+    pub last_render_error: Option<String>,
+    // the terminal was too small to lay the panels out on the last frame
+    pub too_small: bool,
+    // the first core shown in the cpu panel
+    pub cpu_scroll_offset: usize,
+    // how many cores fit in the cpu panel, written by the ui on every frame
+    pub cpu_rows_visible: Cell<usize>,
+    // lines scrolled off the top of the specs, and network interfaces skipped
+    pub specs_scroll: usize,
+    pub network_scroll: usize,
+    // how far each of those can go before the end is in view, written by the ui on every frame
+    pub specs_overflow: Cell<(usize, usize)>,
+    // where the cpu, memory, specs/network and process panels were last drawn, in that
+    // order, for finding what the mouse is over. all empty while they aren't on screen
+    pub layout_rects: Cell<[Rect; 4]>,
+    // index into the rows of the process table
+    pub selected_process: usize,
+}
+
+impl Default for TransientAppState {
+    fn default() -> TransientAppState {
+        TransientAppState {
+            last_render_error: None,
+            too_small: false,
+            cpu_scroll_offset: 0,
+            cpu_rows_visible: Cell::new(usize::MAX),
+            specs_scroll: 0,
+            network_scroll: 0,
+            specs_overflow: Cell::new((0, 0)),
+            layout_rects: Cell::new([Rect::default(); 4]),
+            selected_process: 0,
+        }
+    }
+}
+
+pub struct AppState {
+    pub transient: TransientAppState,
+    // how long the last refresh took, if it overran the refresh interval
+    pub slow_refresh: Option<Duration>,
+    // show process cpu as a share of the total in use rather than of the whole machine
+    pub relative_cpu: bool,
+    pub sorter: ProcessSorter,
+    // how processes sharing a name are combined into one row
+    pub aggregation: Aggregation,
+    // the opposite of the sorter's usual order, toggled by pressing its function key again
+    pub sort_reversed: bool,
+    pub show_nice: bool,
+    pub show_sched_policy: bool,
+    // the memory growth column, for hunting leaks
+    pub show_growth: bool,
+    // the socket count column, counting means walking every process's fds
+    pub show_connection_count: bool,
+    // the state column, hidden while None
+    pub state_display: Option<StateDisplay>,
+    // per pid, only kept up to date while the column is shown
+    pub connection_counts: HashMap<u32, usize>,
+    // fold thread entries into the process that owns them
+    pub merge_threads: bool,
+    pub disk_sorter: DiskSorter,
+    // the highlighted entry of DiskSorter::ALL while the sort menu is open
+    pub disk_sort_menu: Option<usize>,
+    // changed in the column edit mode, from the config when it has a [columns] section
+    pub columns: ColumnLayout,
+    pub column_edit: Option<ColumnEdit>,
+    pub focused: Panel,
+    // row order held by name while frozen, the values keep updating in place
+    pub frozen_order: Option<Vec<String>>,
+    pub search: Option<Search>,
+    // written with m, by pid, and dropped once the process exits. not saved between runs
+    pub notes: HashMap<u32, String>,
+    pub note_edit: Option<NoteEdit>,
+    // processes in each state, for the status bar, counted whenever the processes refresh
+    pub process_state_summary: HashMap<ProcessState, usize>,
+    pub ionice_edit: Option<IoniceEdit>,
+    pub detail: Option<ProcessDetail>,
+    // refreshed more often than the rest, see collector::Collector::watch
+    pub watched_pid: Option<u32>,
+    // the message and when it stops being shown
+    status: Option<(String, Instant)>,
+    // when ctrl-c was last pressed
+    quit_requested: Option<Instant>,
+    // when c last copied a name, a second c straight after copies the pid
+    pub last_copy: Option<Instant>,
+    // when each part of the data last changed, for the marker in its panel's title
+    pub refreshed_at: HashMap<Subsystem, Instant>,
+    // no refreshes are applied while paused, with space or by an alert
+    pub paused: bool,
+    // the alert that paused it, flashed until space acknowledges it
+    pub paused_by: Option<String>,
+    // a snapshot to compare against, taken with b
+    pub baseline: Option<Box<SystemInfo>>,
+    // the network graphs as they were when it was taken
+    pub baseline_history: HashMap<String, NetworkHistory>,
+    // the comparison against it, redone as each refresh lands rather than on every draw
+    pub baseline_report: Option<BaselineReport>,
+    // show the comparison with the baseline in place of the panels
+    pub show_baseline: bool,
+    // from --baseline, where it's loaded from and saved to
+    pub baseline_name: Option<String>,
+    // per interface name
+    pub network_history: HashMap<String, NetworkHistory>,
+    // per core, for spotting thermal throttling
+    pub clock_history: ClockHistory,
+    // errors and drops per second across every interface, since the last full refresh
+    pub network_error_rate: f64,
+    network_errors_checked: Option<Instant>,
+    pub performance_mode: PerformanceMode,
+    // long labels or short ones for small screens, toggled with v
+    pub labels: LabelMode,
+    // what memory, swap, disk and network sizes are shown in, cycled with u
+    pub units: SizeUnit,
+    pub theme: Theme,
+    // from the config, see info::SystemInfo::process_age_histogram
+    pub age_buckets: Vec<Duration>,
+    // from the config too
+    pub clock_stats: Vec<ClockStat>,
+    // bytes of ram, a process using more has its memory cell in red
+    pub memory_highlight: Option<u64>,
+}
+
+impl Default for AppState {
+    fn default() -> AppState {
+        AppState::new()
+    }
+}
+
+// everything that isn't kept between runs starts out as it would on a first run
+impl From<PersistentAppState> for AppState {
+    fn from(persistent: PersistentAppState) -> AppState {
+        AppState {
+            sorter: persistent.sorter,
+            sort_reversed: persistent.sort_reversed,
+            aggregation: persistent.aggregation,
+            relative_cpu: persistent.relative_cpu,
+            merge_threads: persistent.merge_threads,
+            show_nice: persistent.show_nice,
+            show_sched_policy: persistent.show_sched_policy,
+            show_growth: persistent.show_growth,
+            show_connection_count: persistent.show_connection_count,
+            state_display: persistent.state_display,
+            disk_sorter: persistent.disk_sorter,
+            labels: persistent.labels,
+            units: persistent.units,
+            columns: persistent.columns,
+            performance_mode: persistent.performance_mode,
+            ..AppState::new()
+        }
+    }
+}
+
+impl AppState {
+    pub fn new() -> AppState {
+        AppState {
+            transient: TransientAppState::default(),
+            slow_refresh: None,
+            relative_cpu: false,
+            sorter: ProcessSorter::ByCpu,
+            aggregation: Aggregation::Sum,
+            sort_reversed: false,
+            show_nice: false,
+            show_sched_policy: false,
+            show_growth: false,
+            show_connection_count: false,
+            state_display: None,
+            connection_counts: HashMap::new(),
+            merge_threads: true,
+            disk_sorter: DiskSorter::ByUsagePct,
+            disk_sort_menu: None,
+            columns: ColumnLayout::default(),
+            column_edit: None,
+            focused: Panel::Processes,
+            frozen_order: None,
+            search: None,
+            notes: HashMap::new(),
+            note_edit: None,
+            process_state_summary: HashMap::new(),
+            ionice_edit: None,
+            detail: None,
+            watched_pid: None,
+            status: None,
+            quit_requested: None,
+            last_copy: None,
+            // everything was populated just before the first frame
+            refreshed_at: Subsystem::ALL.map(|s| (s, Instant::now())).into(),
+            paused: false,
+            paused_by: None,
+            baseline: None,
+            baseline_history: HashMap::new(),
+            baseline_report: None,
+            show_baseline: false,
+            baseline_name: None,
+            network_history: HashMap::new(),
+            clock_history: ClockHistory::default(),
+            network_error_rate: 0.0,
+            network_errors_checked: None,
+            performance_mode: PerformanceMode::Normal,
+            labels: LabelMode::Full,
+            units: SizeUnit::Auto,
+            theme: Theme::Dark,
+            age_buckets: vec![],
+            clock_stats: ClockStat::ALL.to_vec(),
+            memory_highlight: None,
+        }
+    }
+
+    pub fn persistent(&self) -> PersistentAppState {
+        PersistentAppState {
+            sorter: self.sorter,
+            sort_reversed: self.sort_reversed,
+            aggregation: self.aggregation,
+            relative_cpu: self.relative_cpu,
+            merge_threads: self.merge_threads,
+            show_nice: self.show_nice,
+            show_sched_policy: self.show_sched_policy,
+            show_growth: self.show_growth,
+            show_connection_count: self.show_connection_count,
+            state_display: self.state_display,
+            disk_sorter: self.disk_sorter,
+            labels: self.labels,
+            units: self.units,
+            columns: self.columns.clone(),
+            performance_mode: self.performance_mode,
+        }
+    }
+
+    // called after every full refresh
+    pub fn record_history(&mut self, sys: &SystemInfo) {
+        for network in &sys.usage.networks {
+            let history = self
+                .network_history
+                .entry(network.name.clone())
+                .or_insert_with(NetworkHistory::new);
+
+            history.sent_per_sec.push(network.sent_per_sec);
+            history.recv_per_sec.push(network.recv_per_sec);
+        }
+
+        self.clock_history.push(&sys.usage.cpus);
+    }
+
+    // called after every full refresh with the snapshot it replaced
+    pub fn track_network_errors(&mut self, previous: &SystemInfo, sys: &SystemInfo) {
+        let now = Instant::now();
+
+        self.network_error_rate = match self.network_errors_checked {
+            Some(checked) => sys
+                .usage
+                .network_error_rate(&previous.usage, now.duration_since(checked).as_secs_f64()),
+            None => 0.0,
+        };
+        self.network_errors_checked = Some(now);
+    }
+
+    // keeps the last screenful of cores in view
+    pub fn scroll_cpus(&mut self, offset: usize, core_count: usize) {
+        let visible = self.transient.cpu_rows_visible.get();
+        self.transient.cpu_scroll_offset = offset.min(core_count.saturating_sub(visible));
+    }
+
+    // the panel drawn at that cell, if it's one that can take focus
+    pub fn panel_at(&self, column: u16, row: u16) -> Option<Panel> {
+        let [cpu, _, specs, processes] = self.transient.layout_rects.get();
+        let contains =
+            |r: Rect| column >= r.x && column < r.right() && row >= r.y && row < r.bottom();
+
+        if contains(cpu) {
+            Some(Panel::Cpu)
+        } else if contains(specs) {
+            Some(Panel::Specs)
+        } else if contains(processes) {
+            Some(Panel::Processes)
+        } else {
+            None
+        }
+    }
+
+    pub fn toggle_focus(&mut self, sys: &SystemInfo) {
+        self.focused = match self.focused {
+            Panel::Cpu => Panel::Specs,
+            Panel::Specs => Panel::Processes,
+            Panel::Processes => {
+                // start where the action is
+                let busiest = sys
+                    .usage
+                    .cpus
+                    .iter()
+                    .enumerate()
+                    .max_by(|(_, a), (_, b)| a.usage.total_cmp(&b.usage))
+                    .map(|(i, _)| i)
+                    .unwrap_or_default();
+
+                self.scroll_cpus(busiest, sys.usage.cpus.len());
+                Panel::Cpu
+            }
+        };
+    }
+
+    // while the cpu panel is focused the user is scrolling it themselves, so leave it alone
+    pub fn follow_core_spikes(&mut self, previous: &SystemInfo, current: &SystemInfo) {
+        if self.focused == Panel::Cpu {
+            return;
+        }
+
+        let first = self.transient.cpu_scroll_offset;
+        let visible = first..first.saturating_add(self.transient.cpu_rows_visible.get());
+
+        let spiked = current.usage.cpus.iter().enumerate().position(|(i, cpu)| {
+            let was = previous.usage.cpus.get(i).map_or(0.0, |c| c.usage);
+
+            was < CORE_SPIKE_PERCENT && cpu.usage >= CORE_SPIKE_PERCENT && !visible.contains(&i)
+        });
+
+        if let Some(core) = spiked {
+            self.scroll_cpus(core, current.usage.cpus.len());
+        }
+    }
+
+    // a group's note is the first of its members that has one
+    pub fn note_pid(&self, pids: &[u32]) -> Option<u32> {
+        pids.iter().copied().find(|pid| self.notes.contains_key(pid))
+    }
+
+    pub fn note(&self, pids: &[u32]) -> Option<&str> {
+        self.note_pid(pids).and_then(|pid| self.notes.get(&pid)).map(String::as_str)
+    }
+
+    pub fn compare_with_baseline(&mut self, sys: &SystemInfo) {
+        self.baseline_report = self.baseline.as_ref().map(|b| sys.compare_with_baseline(b));
+    }
+
+    // called after every full refresh, a pid that comes back later is another process
+    pub fn forget_exited_notes(&mut self, sys: &SystemInfo) {
+        self.notes.retain(|pid, _| sys.processes.iter().any(|p| p.pid == *pid));
+    }
+
+    // case insensitive on the name, or the exact pid of any process in the group
+    pub fn matches_search(&self, row: &ProcessGroup) -> bool {
+        let Some(search) = self.search.as_ref().filter(|s| !s.query.is_empty()) else {
+            return false;
+        };
+
+        let query = search.query.to_lowercase();
+
+        row.process.name.to_lowercase().contains(&query)
+            || row.pids.iter().any(|pid| pid.to_string() == search.query)
+    }
+
+    pub fn search_matches(&self, rows: &[ProcessGroup]) -> Vec<usize> {
+        rows.iter()
+            .enumerate()
+            .filter(|(_, p)| self.matches_search(p))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    // selects the first match at or after `from`, wrapping around to the top
+    pub fn next_match(&mut self, rows: &[ProcessGroup], from: usize) {
+        let matches = self.search_matches(rows);
+
+        if let Some(&i) = matches.iter().find(|&&i| i >= from).or(matches.first()) {
+            self.transient.selected_process = i;
+        }
+    }
+
+    // selects the last match before the selection, wrapping around to the bottom
+    pub fn previous_match(&mut self, rows: &[ProcessGroup]) {
+        let matches = self.search_matches(rows);
+        let selected = self.transient.selected_process;
+
+        let previous = matches.iter().rev().find(|&&i| i < selected);
+
+        if let Some(&i) = previous.or(matches.last()) {
+            self.transient.selected_process = i;
+        }
+    }
+
+    // what the process table lists before grouping by name
+    pub fn table_processes<'a>(&self, sys: &'a SystemInfo) -> Vec<&'a ProcessData> {
+        if self.merge_threads {
+            sort::merge_threads(&sys.processes)
+        } else {
+            sys.processes.iter().collect()
+        }
+    }
+
+    pub fn process_rows(&self, sys: &SystemInfo) -> Vec<ProcessGroup> {
+        let mut rows =
+            sort::grouped_processes(&self.table_processes(sys), self.sorter, self.aggregation);
+
+        if self.sort_reversed {
+            rows.reverse();
+        }
+
+        // grouped rows are keyed by name
+        if let Some(order) = &self.frozen_order {
+            let position = order
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (name.as_str(), i))
+                .collect::<HashMap<&str, usize>>();
+
+            // processes started since the freeze go to the bottom, in live order
+            rows.sort_by_key(|row| {
+                position
+                    .get(row.process.name.as_str())
+                    .copied()
+                    .unwrap_or(usize::MAX)
+            });
+        }
+
+        rows
+    }
+
+    // a function key picks its column, or flips the order if it's already the one in use
+    pub fn sort_by(&mut self, sorter: ProcessSorter) {
+        if self.sorter == sorter {
+            self.sort_reversed = !self.sort_reversed;
+        } else {
+            self.sorter = sorter;
+            self.sort_reversed = false;
+        }
+    }
+
+    // the process table's columns in the order they're drawn, leaving out those turned off
+    pub fn visible_columns(&self, sys: &SystemInfo) -> Vec<Column> {
+        // only worth the space on a guest whose hypervisor is actually taking time
+        let show_steal = sys.usage.cpu_steal.is_some_and(|steal| steal > 0.0);
+
+        self.columns
+            .order
+            .iter()
+            .copied()
+            .filter(|column| match column {
+                Column::Nice => self.show_nice,
+                Column::Sched => self.show_sched_policy,
+                Column::Steal => show_steal,
+                Column::Growth => self.show_growth || self.sorter == ProcessSorter::ByGrowth,
+                // a view of its own, sorting by it is what brings it up
+                Column::Faults => self.sorter == ProcessSorter::ByFaults,
+                Column::Conns => self.show_connection_count,
+                Column::Note => !self.notes.is_empty(),
+                Column::State => self.state_display.is_some(),
+                _ => true,
+            })
+            .collect()
+    }
+
+    pub fn start_column_edit(&mut self, sys: &SystemInfo) {
+        let visible = self.visible_columns(sys);
+
+        self.column_edit = Some(ColumnEdit {
+            selected: visible.first().copied().unwrap_or(Column::Pid),
+            original: self.columns.clone(),
+        });
+    }
+
+    // the leak hunting view, the growth column sorted fastest first
+    pub fn toggle_growth(&mut self) {
+        self.show_growth = !self.show_growth;
+
+        if self.show_growth {
+            self.sorter = ProcessSorter::ByGrowth;
+            self.sort_reversed = false;
+        } else if self.sorter == ProcessSorter::ByGrowth {
+            self.sorter = ProcessSorter::ByCpu;
+        }
+    }
+
+    // holds the current live order, also used to re-sort while frozen
+    pub fn freeze_order(&mut self, sys: &SystemInfo) {
+        self.frozen_order = None;
+
+        let order = self
+            .process_rows(sys)
+            .into_iter()
+            .map(|row| row.process.name)
+            .collect();
+        self.frozen_order = Some(order);
+    }
+
+    // shows a short message in the status bar for a few seconds
+    pub fn set_status(&mut self, message: impl Into<String>) {
+        self.set_status_for(message, STATUS_DURATION);
+    }
+
+    pub fn set_status_for(&mut self, message: impl Into<String>, duration: Duration) {
+        self.status = Some((message.into(), Instant::now() + duration));
+    }
+
+    pub fn status_message(&self) -> Option<&str> {
+        self.status
+            .as_ref()
+            .filter(|(_, until)| Instant::now() < *until)
+            .map(|(message, _)| message.as_str())
+    }
+
+    // the first ctrl-c resets the view, a second one soon after quits, returns whether to quit
+    pub fn ctrl_c(&mut self) -> bool {
+        if self
+            .quit_requested
+            .is_some_and(|t| t.elapsed() < QUIT_CONFIRM_WINDOW)
+        {
+            return true;
+        }
+
+        self.search = None;
+        self.sorter = ProcessSorter::ByCpu;
+        self.sort_reversed = false;
+        self.frozen_order = None;
+        self.transient.selected_process = 0;
+        self.quit_requested = Some(Instant::now());
+        self.set_status_for("press ctrl-c again to quit", QUIT_CONFIRM_WINDOW);
+
+        false
+    }
+}
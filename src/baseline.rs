@@ -0,0 +1,268 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{
+    history::NetworkHistory,
+    info::{Cpu, Disk, InterfaceKind, Network, ProcessData, SystemInfo},
+    json::{self, Value},
+    session,
+};
+
+// named baselines are kept beside the saved session, in <state dir>/pidwatch/baselines
+fn named_dir() -> Result<PathBuf, String> {
+    session::default_path()
+        .and_then(|path| Some(path.parent()?.join("baselines")))
+        .ok_or_else(|| "couldn't find a state directory, HOME isn't set".to_string())
+}
+
+pub fn named_path(name: &str) -> Result<PathBuf, String> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("'{}' can't be used as a baseline name", name));
+    }
+
+    Ok(named_dir()?.join(format!("{}.json", name)))
+}
+
+// every named baseline and when it was saved, by name
+pub fn list_named() -> Result<Vec<(String, SystemTime)>, String> {
+    let dir = named_dir()?;
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(format!("couldn't read {}: {}", dir.display(), e)),
+    };
+
+    let mut named = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = path.file_name()?.to_str()?.strip_suffix(".json")?.to_string();
+            let saved = entry.metadata().ok()?.modified().ok()?;
+
+            Some((name, saved))
+        })
+        .collect::<Vec<(String, SystemTime)>>();
+
+    named.sort();
+
+    Ok(named)
+}
+
+// a baseline file only keeps what SystemInfo::compare_with_baseline looks at, and the
+// network graphs as they were when it was taken
+pub fn save(
+    sys: &SystemInfo,
+    network_history: &HashMap<String, NetworkHistory>,
+    path: &Path,
+) -> Result<(), String> {
+    let snapshot = Value::object([
+        (
+            "cpus",
+            Value::Array(
+                sys.usage
+                    .cpus
+                    .iter()
+                    .map(|c| {
+                        Value::object([("name", c.name.as_str().into()), ("usage", c.usage.into())])
+                    })
+                    .collect(),
+            ),
+        ),
+        ("memory", sys.usage.memory.into()),
+        ("total_memory", sys.usage.total_memory.into()),
+        ("swap", sys.usage.swap.into()),
+        ("total_swap", sys.usage.total_swap.into()),
+        (
+            "disks",
+            Value::Array(
+                sys.usage
+                    .disks
+                    .iter()
+                    .map(|d| {
+                        Value::object([
+                            ("mount", d.mount.as_str().into()),
+                            ("used", d.used.into()),
+                            ("total", d.total.into()),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+        (
+            "networks",
+            Value::Array(
+                sys.usage
+                    .networks
+                    .iter()
+                    .map(|n| {
+                        Value::object([
+                            ("name", n.name.as_str().into()),
+                            ("total_sent", n.total_sent.into()),
+                            ("total_recv", n.total_recv.into()),
+                            (
+                                "history",
+                                network_history.get(&n.name).map(NetworkHistory::to_json).into(),
+                            ),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+        (
+            "processes",
+            Value::Array(
+                sys.processes
+                    .iter()
+                    .filter(|p| p.thread_of.is_none())
+                    .map(|p| {
+                        Value::object([
+                            ("pid", p.pid.into()),
+                            ("name", p.name.as_str().into()),
+                            ("cpu_usage", p.cpu_usage.into()),
+                            ("ram", p.ram.into()),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+    ]);
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| format!("couldn't create {}: {}", dir.display(), e))?;
+    }
+
+    fs::write(path, format!("{}\n", snapshot))
+        .map_err(|e| format!("couldn't write {}: {}", path.display(), e))
+}
+
+pub fn load(path: &Path) -> Result<(SystemInfo, HashMap<String, NetworkHistory>), String> {
+    let text =
+        fs::read_to_string(path).map_err(|e| format!("couldn't read {}: {}", path.display(), e))?;
+    let snapshot = json::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let number =
+        |value: &Value, key: &str| value.get(key).and_then(Value::as_f64).unwrap_or_default();
+    let string = |value: &Value, key: &str| {
+        value
+            .get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let array = |key: &str| {
+        snapshot
+            .get(key)
+            .and_then(Value::as_array)
+            .unwrap_or_default()
+    };
+
+    let mut sys = SystemInfo::new();
+
+    sys.usage.cpus = array("cpus")
+        .iter()
+        .map(|c| Cpu {
+            name: string(c, "name"),
+            usage: number(c, "usage") as f32,
+            clock_speed: 0.0,
+            vendor: String::new(),
+            brand: String::new(),
+            temperature: None,
+            steal: None,
+        })
+        .collect();
+
+    sys.usage.memory = number(&snapshot, "memory") as u64;
+    sys.usage.total_memory = number(&snapshot, "total_memory") as u64;
+    sys.usage.swap = number(&snapshot, "swap") as u64;
+    sys.usage.total_swap = number(&snapshot, "total_swap") as u64;
+
+    sys.usage.disks = array("disks")
+        .iter()
+        .map(|d| {
+            let used = number(d, "used") as u64;
+            let total = number(d, "total") as u64;
+
+            Disk {
+                name: String::new(),
+                mount: string(d, "mount"),
+                total,
+                used,
+                free: total.saturating_sub(used),
+                percent: if total == 0 {
+                    0.0
+                } else {
+                    used as f32 / total as f32 * 100.0
+                },
+                fs_type: String::new(),
+                is_removable: false,
+                total_read: 0,
+                total_written: 0,
+                read_per_sec: 0,
+                write_per_sec: 0,
+                inode_used: None,
+                inode_total: None,
+            }
+        })
+        .collect();
+
+    sys.usage.networks = array("networks")
+        .iter()
+        .map(|n| Network {
+            name: string(n, "name"),
+            mac: String::new(),
+            total_sent: number(n, "total_sent") as u64,
+            total_recv: number(n, "total_recv") as u64,
+            total_packets_sent: 0,
+            total_packets_recv: 0,
+            total_errors_sent: 0,
+            total_errors_recv: 0,
+            total_drops_sent: 0,
+            total_drops_recv: 0,
+            sent_per_sec: 0,
+            recv_per_sec: 0,
+            ipv4_addrs: vec![],
+            ipv6_addrs: vec![],
+            kind: InterfaceKind::from_name(&string(n, "name")),
+        })
+        .collect();
+
+    sys.processes = array("processes")
+        .iter()
+        .map(|p| ProcessData {
+            pid: number(p, "pid") as u32,
+            name: string(p, "name"),
+            exe: String::new(),
+            cwd: None,
+            state: String::new(),
+            uid: None,
+            ram: number(p, "ram") as u64,
+            virtual_memory: 0,
+            total_time: 0.0,
+            start_time: 0.0,
+            cpu_usage: number(p, "cpu_usage") as f32,
+            cpu_steal_ms: None,
+            nice: 0,
+            sched_policy: None,
+            parent_pid: None,
+            thread_of: None,
+            exe_deleted: false,
+            children_pids: vec![],
+            memory_growth: None,
+            major_faults: None,
+            threads: None,
+            major_fault_rate: None,
+        })
+        .collect();
+
+    // files saved before the graphs were kept have no history
+    let network_history = array("networks")
+        .iter()
+        .filter_map(|n| Some((string(n, "name"), NetworkHistory::from_json(n.get("history")?))))
+        .collect();
+
+    Ok((sys, network_history))
+}
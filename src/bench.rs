@@ -0,0 +1,125 @@
+use std::{
+    hint::black_box,
+    io,
+    time::{Duration, Instant},
+};
+
+use ratatui::{backend::TestBackend, Terminal};
+
+use crate::{app::AppState, export, info::SystemInfo, json::Value, ui};
+
+// a desktop has a few hundred processes, a busy server thousands
+const PROCESS_COUNTS: [usize; 4] = [100, 1_000, 10_000, 50_000];
+const SAME_NAME_COUNT: usize = 10_000;
+const JSON_COUNT: usize = 10_000;
+const ITERATIONS: u32 = 20;
+
+// the fastest of the runs, the slower ones mostly measure whatever else the machine was doing
+fn fastest(mut f: impl FnMut()) -> Duration {
+    (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+// `count` processes copied from the real ones under new pids, in groups of `group_size`
+// sharing a name so the grouping has something to do
+fn fixture(sys: &SystemInfo, count: usize, group_size: usize) -> SystemInfo {
+    let mut fixture = sys.clone();
+
+    fixture.processes = sys
+        .processes
+        .iter()
+        .cycle()
+        .take(count)
+        .enumerate()
+        .map(|(i, process)| {
+            let mut process = process.clone();
+
+            process.pid = i as u32 + 1;
+            process.name = format!("process-{}", i / group_size);
+            process.parent_pid = None;
+            process.thread_of = None;
+            process.children_pids = vec![];
+
+            process
+        })
+        .collect();
+
+    fixture
+}
+
+// prints how long a refresh of this machine takes, then building the process table's rows
+// and drawing a whole frame for larger and larger process lists
+pub fn run() {
+    let mut sys = SystemInfo::new();
+
+    // the first populate has nothing to reuse yet, so it's reported on its own
+    let start = Instant::now();
+    sys.populate();
+    let first = start.elapsed();
+
+    let steady = fastest(|| sys.populate());
+
+    println!(
+        "populate ({} processes): first {:?}, then {:?}",
+        sys.processes.len(),
+        first,
+        steady
+    );
+
+    if sys.processes.is_empty() {
+        println!("no processes to build fixtures from");
+        return;
+    }
+
+    let state = AppState::new();
+    let mut terminal = Terminal::new(TestBackend::new(160, 45)).expect("test backend");
+
+    for count in PROCESS_COUNTS {
+        let fixture = fixture(&sys, count, 10);
+        let mut network_order = vec![];
+        fixture.usage.extend_network_order(&mut network_order);
+
+        let rows = fastest(|| {
+            black_box(state.process_rows(&fixture));
+        });
+        let frame = fastest(|| {
+            terminal
+                .draw(|frame| ui::render(frame, &fixture, &state, &network_order))
+                .expect("test backend");
+        });
+
+        println!("{:>6} processes: rows {:?}, frame {:?}", count, rows, frame);
+    }
+
+    // a worker pool or a fork bomb, everything in the one group
+    let same_name = fixture(&sys, SAME_NAME_COUNT, SAME_NAME_COUNT);
+    let rows = fastest(|| {
+        black_box(state.process_rows(&same_name));
+    });
+
+    println!(
+        "{:>6} processes sharing a name: rows {:?}",
+        SAME_NAME_COUNT, rows
+    );
+
+    // --stream-json against building the whole document as a Value and then writing it
+    let json_fixture = fixture(&sys, JSON_COUNT, 1);
+    let streaming = fastest(|| {
+        export::write_json_streaming(&json_fixture, io::sink()).expect("sink");
+    });
+    let buffered = fastest(|| {
+        let processes = json_fixture.processes.iter().map(export::process_json).collect();
+        black_box(Value::object([("processes", Value::Array(processes))]).to_string());
+    });
+
+    println!(
+        "{:>6} processes as json: streaming {:?}, buffered {:?}",
+        JSON_COUNT, streaming, buffered
+    );
+}
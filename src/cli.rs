@@ -0,0 +1,259 @@
+use std::{path::PathBuf, time::Duration};
+
+use crate::{
+    app::PerformanceMode,
+    logger::LogField,
+    watch::{WatchCondition, WatchMetric},
+};
+
+pub const USAGE: &str = "usage: pidwatch [options]
+       pidwatch watch --metric <metric> --threshold <percent> [--for <time>] [--clear]
+
+options:
+    --config <path>               read settings from <path> instead of the default location
+    --perf-mode <mode>            start in low, normal or high performance mode rather than
+                                  the one the last run ended in (normal on a first run)
+    --baseline-file <path>        load the baseline from <path> if it exists, and save it there
+    --baseline <name>             the same with a baseline kept by name in pidwatch's state
+                                  directory, so it can be compared against after a restart
+    --list-baselines              list the named baselines and when they were saved
+    --health-json                 print the health score and what went into it as json and
+                                  exit, see the title bar for the same figure
+    --platform                    list what pidwatch can and can't show on this platform
+    --export-csv <path>           write the process list to <path> as csv and exit, - for stdout
+    --stream-json <path>          write the usage figures and process list to <path> as json
+                                  and exit, - for stdout
+    --user <name>                 run as <name> from the start, needs root (unix only)
+    --drop-privileges <name>      switch from root to <name> once the first refresh is done,
+                                  so it's read with root's access (unix only)
+    --headless-monitor <path>     run without the tui, logging events to <path> as json lines
+    --headless-interval <time>    time between polls in headless mode (default 5s)
+    --headless-pid-file <path>    write pidwatch's own pid to <path> in headless mode
+    --alert-net-errors <count>    exit with status 1 once the interfaces have had more than
+                                  <count> errors and dropped packets between them
+    --log <path>                  append a json line to <path> on every refresh while the tui
+                                  runs
+    --log-max-mb <size>           move the log aside to <path>.1 once it's this many MB
+                                  (default 100)
+    --log-keep-rotated <count>    how many moved aside logs to keep (default 3)
+    --log-fields <fields>         what each line holds, from cpu, memory, disks, networks
+                                  and processes (default all of them)
+    --widget                      print a one line summary every refresh instead of the tui,
+                                  for a tmux status line. also what runs when stdout isn't a
+                                  terminal. it can't be combined with --log or a baseline
+    -h, --help                    show this message
+
+watch options, without the tui:
+    --metric <metric>             cpu, memory, swap or disk (the fullest one), as a percentage
+    --threshold <percent>         exit with status 1 once the metric has stayed above this
+    --for <time>                  for this long without a break (default 0s)
+    --clear                       instead wait for it to go above and then stay back below
+    --interval <time>             time between samples (default 1s)
+
+switching user can't be undone, and afterwards pidwatch only sees what that user
+can see. it's refused unless pidwatch runs as root, or the user is the one that
+started it.
+
+times are seconds, or take a ms, s, m, h or d suffix (500ms, 10s, 2m, 1d)";
+
+pub struct Args {
+    pub config: Option<PathBuf>,
+    // None keeps the mode the last run ended in
+    pub perf_mode: Option<PerformanceMode>,
+    pub baseline_file: Option<PathBuf>,
+    pub baseline: Option<String>,
+    pub list_baselines: bool,
+    pub platform: bool,
+    pub health_json: bool,
+    pub export_csv: Option<PathBuf>,
+    pub stream_json: Option<PathBuf>,
+    pub user: Option<String>,
+    pub drop_privileges: Option<String>,
+    pub headless_monitor: Option<PathBuf>,
+    pub headless_interval: Duration,
+    pub headless_pid_file: Option<PathBuf>,
+    pub alert_net_errors: Option<u64>,
+    pub log: Option<PathBuf>,
+    pub log_max_mb: u64,
+    pub log_keep_rotated: usize,
+    pub log_fields: Vec<LogField>,
+    pub widget: bool,
+    // the watch subcommand, with whether it's waiting for the metric to clear and the time
+    // between samples
+    pub watch: Option<(WatchCondition, bool, Duration)>,
+    // only does anything when built with the bench feature, so it's left out of USAGE
+    pub bench: bool,
+    pub help: bool,
+}
+
+// 5, 2.5, 500ms, 10s, 2m, 1h or 1d
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let (number, scale) = if let Some(ms) = value.strip_suffix("ms") {
+        (ms, 0.001)
+    } else if let Some(s) = value.strip_suffix('s') {
+        (s, 1.0)
+    } else if let Some(m) = value.strip_suffix('m') {
+        (m, 60.0)
+    } else if let Some(h) = value.strip_suffix('h') {
+        (h, 3600.0)
+    } else if let Some(d) = value.strip_suffix('d') {
+        (d, 86400.0)
+    } else {
+        (value, 1.0)
+    };
+
+    number
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite() && *n >= 0.0)
+        .map(|n| Duration::from_secs_f64(n * scale))
+        .ok_or_else(|| format!("'{}' is not a valid time", value))
+}
+
+impl Args {
+    pub fn parse() -> Result<Args, String> {
+        Args::parse_from(std::env::args().skip(1))
+    }
+
+    pub fn parse_from(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+        let mut parsed = Args {
+            config: None,
+            perf_mode: None,
+            baseline_file: None,
+            baseline: None,
+            list_baselines: false,
+            platform: false,
+            health_json: false,
+            export_csv: None,
+            stream_json: None,
+            user: None,
+            drop_privileges: None,
+            headless_monitor: None,
+            headless_interval: Duration::from_secs(5),
+            headless_pid_file: None,
+            alert_net_errors: None,
+            log: None,
+            log_max_mb: 100,
+            log_keep_rotated: 3,
+            log_fields: LogField::ALL.to_vec(),
+            widget: false,
+            watch: None,
+            bench: false,
+            help: false,
+        };
+
+        let mut args = args.into_iter();
+
+        // only meaningful after watch
+        let mut watching = false;
+        let mut metric = None;
+        let mut threshold = None;
+        let mut duration = None;
+        let mut clear = false;
+        let mut interval = None;
+
+        while let Some(arg) = args.next() {
+            // both --flag value and --flag=value are accepted
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    (flag.to_string(), Some(value.to_string()))
+                }
+                _ => (arg, None),
+            };
+
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} needs a value", flag))
+            };
+
+            match flag.as_str() {
+                "--config" => parsed.config = Some(PathBuf::from(value()?)),
+                "--perf-mode" => parsed.perf_mode = Some(PerformanceMode::parse(&value()?)?),
+                "--baseline-file" => parsed.baseline_file = Some(PathBuf::from(value()?)),
+                "--baseline" => parsed.baseline = Some(value()?),
+                "--list-baselines" => parsed.list_baselines = true,
+                "--platform" => parsed.platform = true,
+                "--health-json" => parsed.health_json = true,
+                "--export-csv" => parsed.export_csv = Some(PathBuf::from(value()?)),
+                "--stream-json" => parsed.stream_json = Some(PathBuf::from(value()?)),
+                "--user" => parsed.user = Some(value()?),
+                "--drop-privileges" => parsed.drop_privileges = Some(value()?),
+                "--headless-monitor" => parsed.headless_monitor = Some(PathBuf::from(value()?)),
+                "--headless-interval" => parsed.headless_interval = parse_duration(&value()?)?,
+                "--headless-pid-file" => parsed.headless_pid_file = Some(PathBuf::from(value()?)),
+                "--alert-net-errors" => {
+                    let count = value()?;
+
+                    parsed.alert_net_errors = Some(
+                        count
+                            .parse()
+                            .map_err(|_| format!("'{}' is not a valid count", count))?,
+                    )
+                }
+                "--log" => parsed.log = Some(PathBuf::from(value()?)),
+                "--log-max-mb" => {
+                    let size = value()?;
+
+                    parsed.log_max_mb = size
+                        .parse()
+                        .ok()
+                        .filter(|mb| *mb > 0)
+                        .ok_or_else(|| format!("'{}' is not a valid size", size))?
+                }
+                "--log-keep-rotated" => {
+                    let count = value()?;
+
+                    parsed.log_keep_rotated = count
+                        .parse()
+                        .map_err(|_| format!("'{}' is not a valid count", count))?
+                }
+                "--log-fields" => parsed.log_fields = LogField::parse_list(&value()?)?,
+                "--widget" => parsed.widget = true,
+                "watch" => watching = true,
+                "--metric" => metric = Some(WatchMetric::parse(&value()?)?),
+                "--threshold" => {
+                    let percent = value()?;
+
+                    threshold = Some(
+                        percent
+                            .parse::<f64>()
+                            .ok()
+                            .filter(|p| p.is_finite())
+                            .ok_or_else(|| format!("'{}' is not a valid percentage", percent))?,
+                    )
+                }
+                "--for" => duration = Some(parse_duration(&value()?)?),
+                "--clear" => clear = true,
+                "--interval" => interval = Some(parse_duration(&value()?)?),
+                "--bench" => parsed.bench = true,
+                "-h" | "--help" => parsed.help = true,
+                _ => return Err(format!("unknown argument '{}'", flag)),
+            }
+        }
+
+        if watching {
+            let condition = WatchCondition {
+                metric: metric.ok_or("watch needs a --metric")?,
+                threshold: threshold.ok_or("watch needs a --threshold")?,
+                duration: duration.unwrap_or_default(),
+            };
+            let interval = interval
+                .filter(|i| !i.is_zero())
+                .unwrap_or(Duration::from_secs(1));
+
+            parsed.watch = Some((condition, clear, interval));
+        } else if metric.is_some() || threshold.is_some() || duration.is_some() || clear {
+            return Err("--metric, --threshold, --for and --clear only go with watch".to_string());
+        } else if interval.is_some() {
+            return Err("--interval only goes with watch".to_string());
+        }
+
+        if parsed.baseline.is_some() && parsed.baseline_file.is_some() {
+            return Err("--baseline and --baseline-file can't be used together".to_string());
+        }
+
+        Ok(parsed)
+    }
+}
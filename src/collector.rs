@@ -0,0 +1,179 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, SyncSender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    app::PerformanceMode,
+    config::Config,
+    connections,
+    info::{Subsystem, SystemInfo},
+    logger::Logger,
+};
+
+// how often a watched process is refreshed on its own, between full refreshes
+const WATCH_INTERVAL: Duration = Duration::from_millis(250);
+const SCHEDULE_SLACK: Duration = Duration::from_millis(50);
+
+// one part of SystemInfo, refreshed whenever its interval has passed
+struct Scheduled {
+    subsystem: Subsystem,
+    interval: Duration,
+    last: Instant,
+}
+
+impl Scheduled {
+    fn new(subsystem: Subsystem, interval: Duration) -> Scheduled {
+        Scheduled {
+            subsystem,
+            interval,
+            // the caller has just populated everything
+            last: Instant::now(),
+        }
+    }
+}
+
+pub struct Refresh {
+    pub info: SystemInfo,
+    pub took: Duration,
+    // false when only the watched process was refreshed, otherwise whichever
+    // parts were due have been
+    pub full: bool,
+    // the parts that were due, empty when only the watched process was refreshed
+    pub refreshed: Vec<Subsystem>,
+    // --log couldn't write this refresh
+    pub log_error: Option<String>,
+    // sockets per pid, counted with the processes while the column asks for them
+    pub connection_counts: Option<HashMap<u32, usize>>,
+}
+
+// refreshes the data on its own thread so a slow refresh never blocks input or drawing
+pub struct Collector {
+    requests: SyncSender<()>,
+    results: Receiver<Refresh>,
+    watched: Arc<Mutex<Option<u32>>>,
+    mode: Arc<Mutex<PerformanceMode>>,
+    count_connections: Arc<Mutex<bool>>,
+}
+
+impl Collector {
+    // with a logger every full refresh is written to it here, off the ui's thread
+    pub fn spawn(mut info: SystemInfo, config: &Config, mut logger: Option<Logger>) -> Collector {
+        let mut schedule =
+            Subsystem::ALL.map(|subsystem| Scheduled::new(subsystem, config.interval(subsystem)));
+
+        // a zero sized channel only accepts a request while the thread is idle and waiting,
+        // so requests made during a slow refresh are dropped instead of piling up
+        let (requests, request_rx) = mpsc::sync_channel::<()>(0);
+        let (result_tx, results) = mpsc::channel();
+        let watched = Arc::new(Mutex::new(None));
+        let thread_watched = Arc::clone(&watched);
+        let mode = Arc::new(Mutex::new(PerformanceMode::Normal));
+        let thread_mode = Arc::clone(&mode);
+        let count_connections = Arc::new(Mutex::new(false));
+        let thread_count_connections = Arc::clone(&count_connections);
+        // whether the last counts sent are still current, so switching the count on
+        // doesn't wait for the processes to be due
+        let mut counted = false;
+
+        thread::spawn(move || loop {
+            let full = match request_rx.recv_timeout(WATCH_INTERVAL) {
+                Ok(()) => true,
+                Err(RecvTimeoutError::Timeout) => false,
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+
+            let start = Instant::now();
+            let mut refreshed = vec![];
+
+            if full {
+                let mode = *thread_mode.lock().unwrap();
+
+                for scheduled in &mut schedule {
+                    if scheduled.subsystem.expensive() && mode.skips_expensive() {
+                        continue;
+                    }
+
+                    // a little slack so a tick arriving just early doesn't skip a whole interval
+                    let interval = mode.refresh_interval(scheduled.interval);
+
+                    if scheduled.last.elapsed() + SCHEDULE_SLACK >= interval {
+                        info.populate_subsystem(scheduled.subsystem);
+                        scheduled.last = Instant::now();
+                        refreshed.push(scheduled.subsystem);
+                    }
+                }
+            } else if let Some(pid) = *thread_watched.lock().unwrap() {
+                info.refresh_process(pid);
+            } else {
+                continue;
+            }
+
+            let connection_counts = if !*thread_count_connections.lock().unwrap() {
+                counted = false;
+                None
+            } else if full && (!counted || refreshed.contains(&Subsystem::Processes)) {
+                counted = true;
+                Some(connections::connection_counts())
+            } else {
+                None
+            };
+
+            let took = start.elapsed();
+            let log_error = logger
+                .as_mut()
+                .filter(|_| full)
+                .and_then(|logger| logger.write(&info).err());
+
+            let refresh = Refresh {
+                info: info.clone(),
+                took,
+                full,
+                refreshed,
+                log_error,
+                connection_counts,
+            };
+
+            if result_tx.send(refresh).is_err() {
+                break;
+            }
+        });
+
+        Collector {
+            requests,
+            results,
+            watched,
+            mode,
+            count_connections,
+        }
+    }
+
+    // if the collector is still busy with the previous refresh this one is skipped
+    pub fn request(&self) {
+        let _ = self.requests.try_send(());
+    }
+
+    // keeps the given process fresh between full refreshes
+    pub fn watch(&self, pid: Option<u32>) {
+        *self.watched.lock().unwrap() = pid;
+    }
+
+    pub fn set_mode(&self, mode: PerformanceMode) {
+        *self.mode.lock().unwrap() = mode;
+    }
+
+    // walking every process's fds is only worth it while the column is shown
+    pub fn count_connections(&self, count: bool) {
+        *self.count_connections.lock().unwrap() = count;
+    }
+
+    // the newest finished refresh, older ones that weren't picked up yet are stale
+    pub fn latest(&self) -> Option<Refresh> {
+        self.results.try_iter().last()
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+// the process table's columns, the first five are always shown and the rest are toggled
+// from the keyboard, their order and widths come from the [columns] config section
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    Pid,
+    Name,
+    Cpu,
+    Memory,
+    Uptime,
+    State,
+    Nice,
+    Sched,
+    Steal,
+    Growth,
+    Faults,
+    Conns,
+    Note,
+}
+
+// a share of the table's width, relative to the other columns shown
+const DEFAULT_WIDTH: u16 = 10;
+const MAX_WIDTH: u16 = 50;
+
+impl Column {
+    pub const ALL: [Column; 13] = [
+        Column::Pid,
+        Column::Name,
+        Column::Cpu,
+        Column::Memory,
+        Column::Uptime,
+        Column::State,
+        Column::Nice,
+        Column::Sched,
+        Column::Steal,
+        Column::Growth,
+        Column::Faults,
+        Column::Conns,
+        Column::Note,
+    ];
+
+    // the name used in the config file
+    pub fn key(self) -> &'static str {
+        match self {
+            Column::Pid => "pid",
+            Column::Name => "name",
+            Column::Cpu => "cpu",
+            Column::Memory => "memory",
+            Column::Uptime => "uptime",
+            Column::State => "state",
+            Column::Nice => "nice",
+            Column::Sched => "sched",
+            Column::Steal => "steal",
+            Column::Growth => "growth",
+            Column::Faults => "faults",
+            Column::Conns => "conns",
+            Column::Note => "note",
+        }
+    }
+
+    pub fn header(self) -> &'static str {
+        match self {
+            Column::Pid => "PID",
+            Column::Name => "Name",
+            Column::Cpu => "CPU",
+            Column::Memory => "Memory",
+            Column::Uptime => "Uptime",
+            Column::State => "State",
+            Column::Nice => "Nice",
+            Column::Sched => "Sched",
+            Column::Steal => "Steal ⚠",
+            Column::Growth => "Growth",
+            Column::Faults => "Faults/s",
+            Column::Conns => "Conns",
+            Column::Note => "Note",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Column, String> {
+        Column::ALL
+            .into_iter()
+            .find(|c| c.key() == value)
+            .ok_or_else(|| format!("unknown column '{}'", value))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnLayout {
+    // every column in the order they're drawn, whether or not they're shown
+    pub order: Vec<Column>,
+    pub widths: HashMap<Column, u16>,
+}
+
+impl ColumnLayout {
+    pub fn width(&self, column: Column) -> u16 {
+        self.widths.get(&column).copied().unwrap_or(DEFAULT_WIDTH)
+    }
+
+    pub fn resize(&mut self, column: Column, grow: bool) {
+        let width = self.width(column);
+        let width = if grow {
+            (width + 1).min(MAX_WIDTH)
+        } else {
+            width.saturating_sub(1).max(1)
+        };
+
+        self.widths.insert(column, width);
+    }
+
+    // out of range widths are brought back into it, for layouts read back from a file
+    pub fn set_width(&mut self, column: Column, width: u16) {
+        self.widths.insert(column, width.clamp(1, MAX_WIDTH));
+    }
+
+    // swaps places with the next of the `visible` columns on that side, hidden ones in
+    // between stay where they are
+    pub fn move_column(&mut self, column: Column, forward: bool, visible: &[Column]) {
+        let Some(position) = visible.iter().position(|c| *c == column) else {
+            return;
+        };
+
+        let neighbour = if forward {
+            visible.get(position + 1)
+        } else {
+            position.checked_sub(1).and_then(|p| visible.get(p))
+        };
+
+        let (Some(a), Some(b)) = (
+            self.order.iter().position(|c| *c == column),
+            neighbour.and_then(|n| self.order.iter().position(|c| c == n)),
+        ) else {
+            return;
+        };
+
+        self.order.swap(a, b);
+    }
+
+    // a comma separated list of columns, any left out keep their usual place after them
+    pub fn parse_order(value: &str) -> Result<Vec<Column>, String> {
+        let mut order = vec![];
+
+        for key in value.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+            let column = Column::parse(key)?;
+
+            if order.contains(&column) {
+                return Err(format!("'{}' is listed twice", key));
+            }
+
+            order.push(column);
+        }
+
+        for column in Column::ALL {
+            if !order.contains(&column) {
+                order.push(column);
+            }
+        }
+
+        Ok(order)
+    }
+
+    pub fn parse_width(value: &str) -> Result<u16, String> {
+        value
+            .parse::<u16>()
+            .ok()
+            .filter(|w| (1..=MAX_WIDTH).contains(w))
+            .ok_or_else(|| format!("expected a width from 1 to {}, got '{}'", MAX_WIDTH, value))
+    }
+
+    // the [columns] section of the config file that recreates this layout
+    pub fn section(&self) -> String {
+        let mut section = format!(
+            "[columns]\norder = \"{}\"\n",
+            self.order
+                .iter()
+                .map(|c| c.key())
+                .collect::<Vec<&str>>()
+                .join(", ")
+        );
+
+        for column in &self.order {
+            section.push_str(&format!("{} = {}\n", column.key(), self.width(*column)));
+        }
+
+        section
+    }
+}
+
+impl Default for ColumnLayout {
+    fn default() -> ColumnLayout {
+        ColumnLayout {
+            order: Column::ALL.to_vec(),
+            widths: HashMap::new(),
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use std::{collections::HashMap, fs, io, path::PathBuf, time::Duration};
+
+use crate::{
+    cli,
+    columns::{Column, ColumnLayout},
+    info::{MemoryPressure, Subsystem},
+    labels::ClockStat,
+    sort::ProcessSorter,
+    theme::Theme,
+};
+
+// read from $XDG_CONFIG_HOME/pidwatch/config.toml (or ~/.config/...), a small subset of
+// toml: `key = value` lines, `[section]` headers and # comments
+pub struct Config {
+    // how often each part of the data is refreshed
+    pub cpu_interval: Duration,
+    pub process_interval: Duration,
+    pub disk_interval: Duration,
+    pub network_interval: Duration,
+    pub users_interval: Duration,
+
+    // the upper bounds of the process age histogram's buckets, shortest first
+    pub process_age_buckets: Vec<Duration>,
+
+    // function key number to the column it sorts by, pressing it again reverses the order
+    pub sort_keys: HashMap<u8, ProcessSorter>,
+
+    // which of the cores' clock speeds the cpu panel shows
+    pub clock_speed: Vec<ClockStat>,
+
+    // the process table's column order and widths, also saved from the column edit mode.
+    // None without a [columns] section, the last run's layout is kept then
+    pub columns: Option<ColumnLayout>,
+
+    // quit on the first ctrl-c rather than resetting the view and waiting for a second
+    pub ctrl_c_quits: bool,
+
+    // None to follow the terminal's background
+    pub theme: Option<Theme>,
+
+    // bytes of ram past which a process's memory cell stands out, None with "off"
+    pub memory_highlight: Option<u64>,
+
+    // alerts are opt-in, nothing is checked until a threshold is set
+    pub alert_bell: bool,
+    pub alert_notify: bool,
+    pub alert_memory_pressure: Option<MemoryPressure>,
+    // bytes of ram a single process may use
+    pub alert_process_memory: Option<u64>,
+    pub alert_disk_percent: Option<f32>,
+    // the same breach isn't reported again within this long
+    pub alert_interval: Duration,
+    // stop refreshing when an alert fires, so what set it off can be looked at
+    pub alert_pause: bool,
+}
+
+impl Config {
+    pub fn default_path() -> Option<PathBuf> {
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+        Some(config_dir.join("pidwatch").join("config.toml"))
+    }
+
+    // a missing file isn't an error, it just means the defaults
+    pub fn load(path: Option<PathBuf>) -> Result<Config, String> {
+        let Some(path) = path.or_else(Config::default_path) else {
+            return Ok(Config::default());
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => {
+                Config::parse(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(format!("couldn't read {}: {}", path.display(), e)),
+        }
+    }
+
+    // rewrites just the [columns] section, everything else in the file is kept as it is
+    pub fn save_columns(
+        path: Option<PathBuf>,
+        columns: &ColumnLayout,
+    ) -> Result<PathBuf, String> {
+        let path = path
+            .or_else(Config::default_path)
+            .ok_or("nowhere to save to, set $HOME or pass --config")?;
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("couldn't read {}: {}", path.display(), e)),
+        };
+
+        let mut in_columns = false;
+        let mut kept = contents
+            .lines()
+            .filter(|line| {
+                let line = line.trim();
+
+                if line.starts_with('[') {
+                    in_columns = line == "[columns]";
+                }
+
+                !in_columns
+            })
+            .collect::<Vec<&str>>()
+            .join("\n");
+
+        if !kept.trim().is_empty() {
+            kept = format!("{}\n\n", kept.trim_end());
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("couldn't create {}: {}", dir.display(), e))?;
+        }
+
+        fs::write(&path, kept + &columns.section())
+            .map_err(|e| format!("couldn't write {}: {}", path.display(), e))?;
+
+        Ok(path)
+    }
+
+    pub fn parse(contents: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+
+        for (key, value) in parse_values(contents)? {
+            let interval = || cli::parse_duration(&value).map_err(|e| format!("{}: {}", key, e));
+            let flag = || match value.as_str() {
+                "true" => Ok(true),
+                "false" => Ok(false),
+                _ => Err(format!("{}: expected true or false, got '{}'", key, value)),
+            };
+
+            match key.as_str() {
+                "cpu_interval" => config.cpu_interval = interval()?,
+                "process_interval" => config.process_interval = interval()?,
+                "disk_interval" => config.disk_interval = interval()?,
+                "network_interval" => config.network_interval = interval()?,
+                "users_interval" => config.users_interval = interval()?,
+                "process_age_buckets" => {
+                    config.process_age_buckets =
+                        parse_buckets(&value).map_err(|e| format!("{}: {}", key, e))?
+                }
+                "clock_speed" => {
+                    config.clock_speed =
+                        ClockStat::parse_list(&value).map_err(|e| format!("{}: {}", key, e))?
+                }
+                "theme" => {
+                    config.theme = Theme::parse(&value).map_err(|e| format!("{}: {}", key, e))?
+                }
+                "memory_highlight" => {
+                    config.memory_highlight = match value.as_str() {
+                        "off" => None,
+                        _ => Some(parse_size(&value).map_err(|e| format!("{}: {}", key, e))?),
+                    }
+                }
+                "ctrl_c" => {
+                    config.ctrl_c_quits = match value.as_str() {
+                        "quit" => true,
+                        "reset" => false,
+                        _ => {
+                            return Err(format!(
+                                "{}: expected quit or reset, got '{}'",
+                                key, value
+                            ))
+                        }
+                    }
+                }
+                "alerts.bell" => config.alert_bell = flag()?,
+                "alerts.notify" => config.alert_notify = flag()?,
+                "alerts.memory_pressure" => {
+                    config.alert_memory_pressure = Some(match value.as_str() {
+                        "elevated" => MemoryPressure::Elevated,
+                        "high" => MemoryPressure::High,
+                        "critical" => MemoryPressure::Critical,
+                        _ => {
+                            return Err(format!(
+                                "{}: expected elevated, high or critical, got '{}'",
+                                key, value
+                            ))
+                        }
+                    })
+                }
+                "alerts.process_memory" => {
+                    config.alert_process_memory =
+                        Some(parse_size(&value).map_err(|e| format!("{}: {}", key, e))?)
+                }
+                "alerts.disk_percent" => {
+                    config.alert_disk_percent = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("{}: invalid percentage '{}'", key, value))?,
+                    )
+                }
+                "alerts.interval" => config.alert_interval = interval()?,
+                "alerts.pause" => config.alert_pause = flag()?,
+                "columns.order" => {
+                    config.columns.get_or_insert_with(ColumnLayout::default).order =
+                        ColumnLayout::parse_order(&value).map_err(|e| format!("{}: {}", key, e))?
+                }
+                _ if key.starts_with("columns.") => {
+                    let column = Column::parse(key.trim_start_matches("columns."))
+                        .map_err(|e| format!("{}: {}", key, e))?;
+                    let width =
+                        ColumnLayout::parse_width(&value).map_err(|e| format!("{}: {}", key, e))?;
+
+                    config
+                        .columns
+                        .get_or_insert_with(ColumnLayout::default)
+                        .widths
+                        .insert(column, width);
+                }
+                _ if key.starts_with("sort_keys.") => {
+                    let number = key
+                        .strip_prefix("sort_keys.F")
+                        .and_then(|n| n.parse::<u8>().ok())
+                        .filter(|n| (1..=12).contains(n))
+                        .ok_or_else(|| format!("{}: expected a key from F1 to F12", key))?;
+                    let sorter =
+                        ProcessSorter::parse(&value).map_err(|e| format!("{}: {}", key, e))?;
+
+                    config.sort_keys.insert(number, sorter);
+                }
+                _ => return Err(format!("unknown setting '{}'", key)),
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn interval(&self, subsystem: Subsystem) -> Duration {
+        match subsystem {
+            // memory and uptime are cheap enough to go along with the cpus
+            Subsystem::Cpu | Subsystem::Memory | Subsystem::Specs => self.cpu_interval,
+            Subsystem::Processes => self.process_interval,
+            Subsystem::Disks => self.disk_interval,
+            Subsystem::Networks => self.network_interval,
+            Subsystem::Users => self.users_interval,
+        }
+    }
+
+    // the collector has to wake up at least this often to keep every interval
+    pub fn refresh_tick(&self) -> Duration {
+        [
+            self.cpu_interval,
+            self.process_interval,
+            self.disk_interval,
+            self.network_interval,
+            self.users_interval,
+        ]
+        .into_iter()
+        .min()
+        .unwrap_or(Duration::from_secs(1))
+        .max(Duration::from_millis(100))
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            cpu_interval: Duration::from_secs(1),
+            process_interval: Duration::from_secs(1),
+            disk_interval: Duration::from_secs(1),
+            network_interval: Duration::from_secs(1),
+            users_interval: Duration::from_secs(1),
+            // just started, under an hour, under a day and older
+            process_age_buckets: vec![
+                Duration::from_secs(60),
+                Duration::from_secs(3600),
+                Duration::from_secs(86400),
+            ],
+            sort_keys: HashMap::from([
+                (1, ProcessSorter::ByPid),
+                (2, ProcessSorter::ByName),
+                (3, ProcessSorter::ByCpu),
+                (4, ProcessSorter::ByMemory),
+                (5, ProcessSorter::ByUptime),
+            ]),
+            clock_speed: ClockStat::ALL.to_vec(),
+            columns: None,
+            ctrl_c_quits: false,
+            theme: None,
+            memory_highlight: Some(1 << 30),
+            alert_bell: false,
+            alert_notify: false,
+            alert_memory_pressure: None,
+            alert_process_memory: None,
+            alert_disk_percent: None,
+            alert_interval: Duration::from_secs(60),
+            alert_pause: false,
+        }
+    }
+}
+
+// a comma separated list of times, e.g. "1m, 1h, 1d"
+fn parse_buckets(value: &str) -> Result<Vec<Duration>, String> {
+    let buckets = value
+        .split(',')
+        .map(|b| cli::parse_duration(b.trim()))
+        .collect::<Result<Vec<Duration>, String>>()?;
+
+    if buckets.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err("buckets have to go from shortest to longest".to_string());
+    }
+
+    Ok(buckets)
+}
+
+// plain bytes or with a K, M or G suffix (KB, MB and GB work too), in powers of 1024
+fn parse_size(value: &str) -> Result<u64, String> {
+    let upper = value.trim().to_ascii_uppercase();
+    let number = upper.strip_suffix('B').unwrap_or(&upper);
+
+    let (number, multiplier) = if let Some(n) = number.strip_suffix('K') {
+        (n, 1 << 10)
+    } else if let Some(n) = number.strip_suffix('M') {
+        (n, 1 << 20)
+    } else if let Some(n) = number.strip_suffix('G') {
+        (n, 1 << 30)
+    } else {
+        (number, 1)
+    };
+
+    number
+        .trim()
+        .parse::<f64>()
+        .map(|n| (n * multiplier as f64) as u64)
+        .map_err(|_| format!("invalid size '{}'", value))
+}
+
+// keys inside a section come back as `section.key`, quotes around values are dropped
+fn parse_values(contents: &str) -> Result<HashMap<String, String>, String> {
+    let mut values = HashMap::new();
+    let mut section = String::new();
+
+    for (number, line) in contents.lines().enumerate() {
+        let line = match line.split_once('#') {
+            // a # inside a quoted value isn't a comment
+            Some((before, _)) if before.matches('"').count() % 2 == 0 => before,
+            _ => line,
+        }
+        .trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_string();
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {}: expected `key = value`", number + 1));
+        };
+
+        let key = if section.is_empty() {
+            key.trim().to_string()
+        } else {
+            format!("{}.{}", section, key.trim())
+        };
+
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+
+        values.insert(key, value.to_string());
+    }
+
+    Ok(values)
+}
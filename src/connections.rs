@@ -0,0 +1,194 @@
+use std::{collections::HashMap, net::SocketAddr};
+
+#[cfg(target_os = "linux")]
+use std::{collections::HashSet, fs};
+
+// one socket a process holds, as the kernel lists it in /proc/net
+#[derive(Debug, Clone)]
+pub struct Connection {
+    pub protocol: &'static str,
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    // tcp states like ESTABLISHED, udp sockets are always "UNCONN" or "ESTABLISHED"
+    pub state: &'static str,
+}
+
+impl Connection {
+    pub fn is_listening(&self) -> bool {
+        self.state == "LISTEN" || (self.protocol.starts_with("udp") && self.state == "UNCONN")
+    }
+}
+
+// the sockets are matched to processes through the inodes their fds point at, and read from
+// the process's own /proc/<pid>/net so one in another network namespace still finds its own
+#[cfg(target_os = "linux")]
+pub fn process_connections(pid: u32) -> Vec<Connection> {
+    let inodes = socket_inodes(pid);
+
+    if inodes.is_empty() {
+        return vec![];
+    }
+
+    read_sockets(&format!("/proc/{}/net", pid))
+        .into_iter()
+        .filter(|(inode, _)| inodes.contains(inode))
+        .map(|(_, connection)| connection)
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn process_connections(_pid: u32) -> Vec<Connection> {
+    vec![]
+}
+
+// for every process at once, reading the socket tables only the once
+#[cfg(target_os = "linux")]
+pub fn connection_counts() -> HashMap<u32, usize> {
+    let sockets = read_sockets("/proc/net")
+        .into_iter()
+        .map(|(inode, _)| inode)
+        .collect::<HashSet<u64>>();
+
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return HashMap::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok())
+        .map(|pid| {
+            let count = socket_inodes(pid)
+                .iter()
+                .filter(|inode| sockets.contains(inode))
+                .count();
+
+            (pid, count)
+        })
+        .filter(|(_, count)| *count > 0)
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn connection_counts() -> HashMap<u32, usize> {
+    HashMap::new()
+}
+
+// each socket fd links to "socket:[<inode>]"
+#[cfg(target_os = "linux")]
+fn socket_inodes(pid: u32) -> HashSet<u64> {
+    let Ok(fds) = fs::read_dir(format!("/proc/{}/fd", pid)) else {
+        return HashSet::new();
+    };
+
+    fds.filter_map(|fd| fs::read_link(fd.ok()?.path()).ok())
+        .filter_map(|target| {
+            let target = target.to_string_lossy();
+            target
+                .strip_prefix("socket:[")?
+                .strip_suffix(']')?
+                .parse::<u64>()
+                .ok()
+        })
+        .collect()
+}
+
+// every tcp and udp socket in the tables under `net`, with its inode
+#[cfg(target_os = "linux")]
+fn read_sockets(net: &str) -> Vec<(u64, Connection)> {
+    let mut sockets = vec![];
+
+    for protocol in ["tcp", "tcp6", "udp", "udp6"] {
+        let Ok(table) = fs::read_to_string(format!("{}/{}", net, protocol)) else {
+            continue;
+        };
+
+        // the first line is a header
+        sockets.extend(
+            table
+                .lines()
+                .skip(1)
+                .filter_map(|line| parse_line(protocol, line)),
+        );
+    }
+
+    sockets
+}
+
+// sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode
+#[cfg(target_os = "linux")]
+fn parse_line(protocol: &'static str, line: &str) -> Option<(u64, Connection)> {
+    let fields = line.split_whitespace().collect::<Vec<&str>>();
+
+    let local = parse_address(fields.get(1)?)?;
+    let remote = parse_address(fields.get(2)?)?;
+    let state = u8::from_str_radix(fields.get(3)?, 16).ok()?;
+    let inode = fields.get(9)?.parse().ok()?;
+
+    let state = if protocol.starts_with("udp") {
+        // udp reuses the tcp numbering, 1 when connect()ed and 7 (CLOSE) otherwise
+        if state == 1 {
+            "ESTABLISHED"
+        } else {
+            "UNCONN"
+        }
+    } else {
+        tcp_state(state)
+    };
+
+    Some((
+        inode,
+        Connection {
+            protocol,
+            local,
+            remote,
+            state,
+        },
+    ))
+}
+
+// the TCP_* states from include/net/tcp_states.h
+#[cfg(target_os = "linux")]
+fn tcp_state(state: u8) -> &'static str {
+    match state {
+        0x01 => "ESTABLISHED",
+        0x02 => "SYN_SENT",
+        0x03 => "SYN_RECV",
+        0x04 => "FIN_WAIT1",
+        0x05 => "FIN_WAIT2",
+        0x06 => "TIME_WAIT",
+        0x07 => "CLOSE",
+        0x08 => "CLOSE_WAIT",
+        0x09 => "LAST_ACK",
+        0x0A => "LISTEN",
+        0x0B => "CLOSING",
+        _ => "UNKNOWN",
+    }
+}
+
+// "0100007F:0035" is 127.0.0.1:53, the address bytes are printed as native endian 32 bit words
+#[cfg(target_os = "linux")]
+fn parse_address(field: &str) -> Option<SocketAddr> {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    let (address, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+
+    let words = (0..address.len() / 8)
+        .map(|i| u32::from_str_radix(&address[i * 8..i * 8 + 8], 16).ok())
+        .collect::<Option<Vec<u32>>>()?;
+
+    let ip = match words[..] {
+        [word] => IpAddr::V4(Ipv4Addr::from(word.to_ne_bytes())),
+        [a, b, c, d] => {
+            let mut octets = [0u8; 16];
+
+            for (i, word) in [a, b, c, d].into_iter().enumerate() {
+                octets[i * 4..i * 4 + 4].copy_from_slice(&word.to_ne_bytes());
+            }
+
+            IpAddr::V6(Ipv6Addr::from(octets))
+        }
+        _ => return None,
+    };
+
+    Some(SocketAddr::new(ip, port))
+}
@@ -0,0 +1,55 @@
+use std::{
+    io,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+#[cfg(target_os = "macos")]
+const FILE_MANAGER: &str = "open";
+#[cfg(target_os = "windows")]
+const FILE_MANAGER: &str = "explorer";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const FILE_MANAGER: &str = "xdg-open";
+
+// macos and windows always have a desktop, elsewhere there has to be a display server
+pub fn has_display() -> bool {
+    cfg!(any(target_os = "macos", target_os = "windows"))
+        || std::env::var_os("DISPLAY").is_some()
+        || std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+// notify-send is part of libnotify, most linux desktops have it
+#[cfg(not(target_os = "macos"))]
+pub fn notify(summary: &str, body: &str) -> io::Result<()> {
+    Command::new("notify-send")
+        .args([summary, body])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+pub fn notify(summary: &str, body: &str) -> io::Result<()> {
+    let script = format!("display notification {:?} with title {:?}", body, summary);
+
+    Command::new("osascript")
+        .args(["-e", &script])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+pub fn open_in_file_manager(path: &Path) -> io::Result<()> {
+    // detach the output, anything printed would end up on top of the tui
+    Command::new(FILE_MANAGER)
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
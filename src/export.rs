@@ -0,0 +1,496 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+
+use crate::{
+    info::{ProcessData, SystemInfo},
+    json::Value,
+    sort::ProcessGroup,
+    util,
+};
+
+// rows of the process table in the markdown summary
+const MARKDOWN_PROCESSES: usize = 5;
+
+const CSV_COLUMNS: [&str; 10] = [
+    "pid",
+    "name",
+    "exe",
+    "state",
+    "cpu_usage",
+    "ram",
+    "virtual_memory",
+    "start_time",
+    "nice",
+    "parent_pid",
+];
+
+// the process list as csv, one row per process
+pub struct CsvReporter {
+    separator: char,
+}
+
+impl CsvReporter {
+    pub fn new() -> CsvReporter {
+        CsvReporter { separator: ',' }
+    }
+
+    // rows go out as the iterator yields them, nothing is collected first
+    pub fn write_streaming<'a, W: Write>(
+        &self,
+        processes: impl Iterator<Item = &'a ProcessData>,
+        mut writer: W,
+    ) -> io::Result<()> {
+        self.write_row(&mut writer, CSV_COLUMNS.iter().map(|c| c.to_string()))?;
+
+        for process in processes {
+            self.write_row(
+                &mut writer,
+                [
+                    process.pid.to_string(),
+                    process.name.clone(),
+                    process.exe.clone(),
+                    process.state.clone(),
+                    format!("{:.2}", process.cpu_usage),
+                    process.ram.to_string(),
+                    process.virtual_memory.to_string(),
+                    process.start_time.to_string(),
+                    process.nice.to_string(),
+                    process
+                        .parent_pid
+                        .map(|p| p.to_string())
+                        .unwrap_or_default(),
+                ]
+                .into_iter(),
+            )?;
+        }
+
+        writer.flush()
+    }
+
+    fn write_row(
+        &self,
+        writer: &mut impl Write,
+        fields: impl Iterator<Item = String>,
+    ) -> io::Result<()> {
+        let line = fields
+            .map(|field| self.quote(field))
+            .collect::<Vec<String>>()
+            .join(&self.separator.to_string());
+
+        writeln!(writer, "{}", line)
+    }
+
+    // fields holding the separator, a quote or a newline are quoted, with quotes doubled
+    fn quote(&self, field: String) -> String {
+        if field.contains([self.separator, '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field
+        }
+    }
+}
+
+pub fn process_json(process: &ProcessData) -> Value {
+    Value::object([
+        ("pid", process.pid.into()),
+        ("name", process.name.as_str().into()),
+        ("exe", process.exe.as_str().into()),
+        ("state", process.state.as_str().into()),
+        ("cpu_usage", process.cpu_usage.into()),
+        ("ram", process.ram.into()),
+        ("virtual_memory", process.virtual_memory.into()),
+        ("start_time", process.start_time.into()),
+        ("nice", (process.nice as f64).into()),
+        ("parent_pid", process.parent_pid.into()),
+    ])
+}
+
+// one json object, the usage figures and then the process list. each process is written as
+// it's reached, so however many there are only one is held as a Value at a time
+pub fn write_json_streaming<W: Write>(sys: &SystemInfo, mut writer: W) -> io::Result<()> {
+    let usage = &sys.usage;
+    let fields: [(&str, Value); 6] = [
+        ("timestamp_utc", util::format_timestamp_utc(SystemTime::now()).into()),
+        ("average_cpu_usage", usage.average_cpu_usage().into()),
+        ("memory", usage.memory.into()),
+        ("total_memory", usage.total_memory.into()),
+        ("swap", usage.swap.into()),
+        ("total_swap", usage.total_swap.into()),
+    ];
+
+    writer.write_all(b"{")?;
+
+    for (key, value) in fields {
+        write!(writer, "{}:{},", Value::from(key), value)?;
+    }
+
+    writer.write_all(b"\"processes\":[")?;
+
+    for (i, process) in sys.processes.iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b",")?;
+        }
+
+        write!(writer, "{}", process_json(process))?;
+    }
+
+    writer.write_all(b"]}\n")?;
+    writer.flush()
+}
+
+// pipes would end the cell early and newlines the row
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+// a short summary for an issue or a chat, the specs, the headline figures and the top of
+// the process table as it's currently sorted
+pub fn markdown_summary(sys: &SystemInfo, rows: &[ProcessGroup]) -> String {
+    let spec = &sys.spec;
+    let usage = &sys.usage;
+
+    let mut out = format!(
+        "**{}**: {}, kernel {}, {}, up {}\n\n",
+        markdown_cell(&spec.hostname),
+        markdown_cell(&spec.os),
+        markdown_cell(&spec.kernel),
+        spec.architecture,
+        util::format_duration(spec.uptime.parse().unwrap_or_default())
+    );
+
+    out.push_str(&format!(
+        "- CPU: {:.1}% across {} cores\n",
+        usage.average_cpu_usage(),
+        usage.cpus.len()
+    ));
+    out.push_str(&format!(
+        "- Memory: {} of {} used ({:.1}%), {} available\n",
+        util::format_bytes(usage.memory),
+        util::format_bytes(usage.total_memory),
+        usage.memory_percent(),
+        util::format_bytes(usage.available_memory)
+    ));
+    out.push_str(&format!(
+        "- Swap: {} of {} used\n\n",
+        util::format_bytes(usage.swap),
+        util::format_bytes(usage.total_swap)
+    ));
+
+    out.push_str("| PID | Name | CPU | Memory |\n|---:|---|---:|---:|\n");
+
+    for row in rows.iter().take(MARKDOWN_PROCESSES) {
+        let pid = match row.pid() {
+            Some(pid) => pid.to_string(),
+            None => format!("{} procs", row.pids.len()),
+        };
+
+        out.push_str(&format!(
+            "| {} | {} | {:.1}% | {} |\n",
+            pid,
+            markdown_cell(&row.process.name),
+            row.process.cpu_usage,
+            util::format_bytes(row.process.ram)
+        ));
+    }
+
+    out
+}
+
+// what's on screen, cell for cell, as opposed to the data behind it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ansi,
+    Html,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Ansi => "txt",
+            Format::Html => "html",
+        }
+    }
+}
+
+// written to the working directory as pidwatch-<unix time>.txt or .html
+pub fn save(buffer: &Buffer, format: Format) -> io::Result<PathBuf> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let path = PathBuf::from(format!("pidwatch-{}.{}", now, format.extension()));
+
+    let contents = match format {
+        Format::Ansi => to_ansi(buffer),
+        Format::Html => to_html(buffer),
+    };
+
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+fn to_ansi(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    let area = buffer.area;
+
+    for y in area.top()..area.bottom() {
+        let mut previous = None;
+
+        for x in area.left()..area.right() {
+            let cell = buffer.get(x, y);
+            let style = (cell.fg, cell.bg, cell.modifier);
+
+            // only emit an escape when the style actually changes
+            if previous != Some(style) {
+                out.push_str("\x1b[0");
+
+                for code in modifier_codes(cell.modifier) {
+                    out.push_str(&format!(";{}", code));
+                }
+
+                if let Some(fg) = ansi_color(cell.fg, false) {
+                    out.push_str(&format!(";{}", fg));
+                }
+
+                if let Some(bg) = ansi_color(cell.bg, true) {
+                    out.push_str(&format!(";{}", bg));
+                }
+
+                out.push('m');
+                previous = Some(style);
+            }
+
+            out.push_str(cell.symbol());
+        }
+
+        out.push_str("\x1b[0m\n");
+    }
+
+    out
+}
+
+fn to_html(buffer: &Buffer) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>pidwatch</title>\n</head>\n\
+         <body style=\"background:#000;color:#e5e5e5\">\n<pre style=\"font-family:monospace\">\n",
+    );
+    let area = buffer.area;
+
+    for y in area.top()..area.bottom() {
+        // cells sharing a style go into one span
+        let mut run_css = String::new();
+        let mut run = String::new();
+
+        for x in area.left()..area.right() {
+            let cell = buffer.get(x, y);
+
+            let mut fg = html_color(cell.fg);
+            let mut bg = html_color(cell.bg);
+
+            if cell.modifier.contains(Modifier::REVERSED) {
+                // reset colors swap into the page's own ones
+                (fg, bg) = (
+                    Some(bg.unwrap_or("#000000".to_string())),
+                    Some(fg.unwrap_or("#e5e5e5".to_string())),
+                );
+            }
+
+            let mut css = String::new();
+
+            if let Some(fg) = fg {
+                css.push_str(&format!("color:{};", fg));
+            }
+
+            if let Some(bg) = bg {
+                css.push_str(&format!("background:{};", bg));
+            }
+
+            if cell.modifier.contains(Modifier::BOLD) {
+                css.push_str("font-weight:bold;");
+            }
+
+            if cell.modifier.contains(Modifier::DIM) {
+                css.push_str("opacity:0.6;");
+            }
+
+            if cell.modifier.contains(Modifier::ITALIC) {
+                css.push_str("font-style:italic;");
+            }
+
+            if cell.modifier.contains(Modifier::UNDERLINED) {
+                css.push_str("text-decoration:underline;");
+            }
+
+            if css != run_css {
+                push_span(&mut out, &run_css, &run);
+                run.clear();
+                run_css = css;
+            }
+
+            run.push_str(&escape_html(cell.symbol()));
+        }
+
+        push_span(&mut out, &run_css, &run);
+        out.push('\n');
+    }
+
+    out.push_str("</pre>\n</body>\n</html>\n");
+    out
+}
+
+fn push_span(out: &mut String, css: &str, text: &str) {
+    if css.is_empty() {
+        out.push_str(text);
+    } else if !text.is_empty() {
+        out.push_str(&format!("<span style=\"{}\">{}</span>", css, text));
+    }
+}
+
+fn modifier_codes(modifier: Modifier) -> Vec<u8> {
+    [
+        (Modifier::BOLD, 1),
+        (Modifier::DIM, 2),
+        (Modifier::ITALIC, 3),
+        (Modifier::UNDERLINED, 4),
+        (Modifier::SLOW_BLINK, 5),
+        (Modifier::REVERSED, 7),
+        (Modifier::CROSSED_OUT, 9),
+    ]
+    .into_iter()
+    .filter(|(m, _)| modifier.contains(*m))
+    .map(|(_, code)| code)
+    .collect()
+}
+
+// None for Reset, which is the terminal's own default
+fn ansi_color(color: Color, background: bool) -> Option<String> {
+    let offset = if background { 10 } else { 0 };
+
+    let basic = match color {
+        Color::Reset => return None,
+        Color::Black => 30,
+        Color::Red => 31,
+        Color::Green => 32,
+        Color::Yellow => 33,
+        Color::Blue => 34,
+        Color::Magenta => 35,
+        Color::Cyan => 36,
+        Color::Gray => 37,
+        Color::DarkGray => 90,
+        Color::LightRed => 91,
+        Color::LightGreen => 92,
+        Color::LightYellow => 93,
+        Color::LightBlue => 94,
+        Color::LightMagenta => 95,
+        Color::LightCyan => 96,
+        Color::White => 97,
+        Color::Indexed(i) => return Some(format!("{};5;{}", 38 + offset, i)),
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{};{};{}", 38 + offset, r, g, b)),
+    };
+
+    Some((basic + offset).to_string())
+}
+
+fn html_color(color: Color) -> Option<String> {
+    let index = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => return Some(format!("#{:02x}{:02x}{:02x}", r, g, b)),
+        Color::Indexed(i) => i,
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+    };
+
+    let (r, g, b) = xterm_rgb(index);
+    Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+// the standard xterm 256 color palette
+fn xterm_rgb(index: u8) -> (u8, u8, u8) {
+    const SYSTEM: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+
+    match index {
+        0..=15 => SYSTEM[index as usize],
+        16..=231 => {
+            // a 6x6x6 cube
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = index - 16;
+            (level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        _ => {
+            let grey = 8 + (index - 232) * 10;
+            (grey, grey, grey)
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    #[test]
+    fn csv_header_comes_before_the_rows() {
+        let processes = [
+            ProcessData::named(1, "init"),
+            ProcessData::named(42, "say \"hi\", twice"),
+        ];
+        let mut output = Cursor::new(vec![]);
+
+        CsvReporter::new().write_streaming(processes.iter(), &mut output).unwrap();
+
+        let output = String::from_utf8(output.into_inner()).unwrap();
+        let lines = output.lines().collect::<Vec<&str>>();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_COLUMNS.join(","));
+        assert!(lines[1].starts_with("1,init,"));
+        assert!(lines[2].starts_with("42,\"say \"\"hi\"\", twice\","));
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    collections::HashSet,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    info::SystemInfo,
+    json::Value,
+    util,
+};
+
+// a core jumping by at least this many percentage points between polls is a spike
+const CPU_SPIKE_POINTS: f32 = 50.0;
+// disk usage percentage that's logged when crossed in either direction
+const DISK_THRESHOLD: f32 = 90.0;
+// network errors per second on one interface that count as a spike
+const NET_ERROR_SPIKE_RATE: f64 = 1.0;
+
+fn event(event_type: &str, data: Value) -> Value {
+    Value::object([
+        ("timestamp_utc", util::format_timestamp_utc(SystemTime::now()).into()),
+        ("event_type", event_type.into()),
+        ("data", data),
+    ])
+}
+
+fn detect_events(previous: &SystemInfo, current: &SystemInfo, interval: Duration) -> Vec<Value> {
+    let mut events = vec![];
+
+    let previous_pids = previous.processes.iter().map(|p| p.pid).collect::<HashSet<u32>>();
+    let current_pids = current.processes.iter().map(|p| p.pid).collect::<HashSet<u32>>();
+
+    for process in &current.processes {
+        if !previous_pids.contains(&process.pid) {
+            events.push(event(
+                "process_started",
+                Value::object([
+                    ("pid", process.pid.into()),
+                    ("name", process.name.clone().into()),
+                    ("exe", process.exe.clone().into()),
+                ]),
+            ));
+        }
+    }
+
+    for process in &previous.processes {
+        if !current_pids.contains(&process.pid) {
+            // sysinfo can't tell us how a process we didn't spawn exited
+            events.push(event(
+                "process_exited",
+                Value::object([
+                    ("pid", process.pid.into()),
+                    ("name", process.name.clone().into()),
+                    ("exit_code", Value::Null),
+                ]),
+            ));
+        }
+    }
+
+    let (old_pressure, new_pressure) = (
+        previous.memory_pressure_level(),
+        current.memory_pressure_level(),
+    );
+
+    if old_pressure != new_pressure {
+        events.push(event(
+            "memory_pressure_changed",
+            Value::object([
+                ("from", old_pressure.description().into()),
+                ("to", new_pressure.description().into()),
+            ]),
+        ));
+    }
+
+    for (core, (old, new)) in previous.usage.cpus.iter().zip(&current.usage.cpus).enumerate() {
+        if new.usage - old.usage >= CPU_SPIKE_POINTS {
+            events.push(event(
+                "cpu_spike",
+                Value::object([
+                    ("core", core.into()),
+                    ("old_pct", old.usage.into()),
+                    ("new_pct", new.usage.into()),
+                ]),
+            ));
+        }
+    }
+
+    for disk in &current.usage.disks {
+        let Some(old) = previous.usage.disks.iter().find(|d| d.mount == disk.mount) else {
+            continue;
+        };
+
+        if (old.percent < DISK_THRESHOLD) != (disk.percent < DISK_THRESHOLD) {
+            events.push(event(
+                "disk_threshold_crossed",
+                Value::object([
+                    ("mount", disk.mount.clone().into()),
+                    ("threshold_pct", DISK_THRESHOLD.into()),
+                    ("old_pct", old.percent.into()),
+                    ("new_pct", disk.percent.into()),
+                    (
+                        "direction",
+                        if disk.percent >= DISK_THRESHOLD { "above" } else { "below" }.into(),
+                    ),
+                ]),
+            ));
+        }
+    }
+
+    for network in &current.usage.networks {
+        let Some(old) = previous.usage.networks.iter().find(|n| n.name == network.name) else {
+            continue;
+        };
+
+        let new_errors = (network.total_errors_sent + network.total_errors_recv)
+            .saturating_sub(old.total_errors_sent + old.total_errors_recv);
+        let rate = new_errors as f64 / interval.as_secs_f64().max(0.001);
+
+        if rate >= NET_ERROR_SPIKE_RATE {
+            events.push(event(
+                "network_error_spike",
+                Value::object([
+                    ("interface", network.name.clone().into()),
+                    ("errors", new_errors.into()),
+                    ("errors_per_sec", rate.into()),
+                ]),
+            ));
+        }
+    }
+
+    events
+}
+
+// polls, appending one json object per line to log_path for everything noteworthy, until
+// the interfaces have had more than `net_error_limit` errors and drops
+pub fn run(
+    log_path: &Path,
+    interval: Duration,
+    pid_file: Option<&Path>,
+    net_error_limit: Option<u64>,
+) -> io::Result<()> {
+    if let Some(pid_file) = pid_file {
+        fs::write(pid_file, format!("{}\n", std::process::id()))?;
+    }
+
+    let mut log = OpenOptions::new().create(true).append(true).open(log_path)?;
+
+    let mut previous = SystemInfo::new();
+    previous.populate();
+
+    loop {
+        thread::sleep(interval);
+
+        let mut current = previous.clone();
+        current.populate();
+
+        for event in detect_events(&previous, &current, interval) {
+            writeln!(log, "{}", event)?;
+        }
+
+        let errors = current.usage.total_network_errors();
+
+        if let Some(limit) = net_error_limit.filter(|limit| errors > *limit) {
+            writeln!(
+                log,
+                "{}",
+                event(
+                    "network_error_limit",
+                    Value::object([("errors", errors.into()), ("limit", limit.into())]),
+                )
+            )?;
+            log.flush()?;
+
+            return Ok(());
+        }
+
+        log.flush()?;
+        previous = current;
+    }
+}
@@ -0,0 +1,255 @@
+use std::{collections::VecDeque, time::Instant};
+
+use crate::{info::Cpu, json::Value};
+
+// keeps the last `capacity` values pushed, dropping the oldest
+#[derive(Debug, Clone)]
+pub struct RingBuffer<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> RingBuffer<T> {
+    pub fn new(capacity: usize) -> RingBuffer<T> {
+        RingBuffer {
+            items: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.items.len() == self.capacity {
+            self.items.pop_front();
+        }
+
+        if self.capacity > 0 {
+            self.items.push_back(item);
+        }
+    }
+
+    // oldest first
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.items.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    // an array read back oldest first, the capacity isn't saved with it so it's whatever
+    // the caller uses now. with more items than that only the newest are kept, and
+    // anything `item` can't read is skipped
+    pub fn from_json(
+        value: &Value,
+        capacity: usize,
+        item: impl Fn(&Value) -> Option<T>,
+    ) -> RingBuffer<T> {
+        let mut buffer = RingBuffer::new(capacity);
+
+        for value in value.as_array().unwrap_or_default() {
+            if let Some(item) = item(value) {
+                buffer.push(item);
+            }
+        }
+
+        buffer
+    }
+}
+
+impl<T: Clone + Into<Value>> RingBuffer<T> {
+    // a json array, oldest first
+    pub fn to_json(&self) -> Value {
+        Value::Array(self.items.iter().cloned().map(Into::into).collect())
+    }
+}
+
+// samples kept for each network sparkline
+pub const NETWORK_HISTORY_LEN: usize = 30;
+
+#[derive(Debug, Clone)]
+pub struct NetworkHistory {
+    pub sent_per_sec: RingBuffer<u64>,
+    pub recv_per_sec: RingBuffer<u64>,
+}
+
+impl NetworkHistory {
+    pub fn new() -> NetworkHistory {
+        NetworkHistory {
+            sent_per_sec: RingBuffer::new(NETWORK_HISTORY_LEN),
+            recv_per_sec: RingBuffer::new(NETWORK_HISTORY_LEN),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        Value::object([
+            ("sent_per_sec", self.sent_per_sec.to_json()),
+            ("recv_per_sec", self.recv_per_sec.to_json()),
+        ])
+    }
+
+    pub fn from_json(value: &Value) -> NetworkHistory {
+        let rates = |key: &str| {
+            RingBuffer::from_json(
+                value.get(key).unwrap_or(&Value::Null),
+                NETWORK_HISTORY_LEN,
+                |rate| rate.as_f64().map(|r| r as u64),
+            )
+        };
+
+        NetworkHistory {
+            sent_per_sec: rates("sent_per_sec"),
+            recv_per_sec: rates("recv_per_sec"),
+        }
+    }
+}
+
+// samples of each process's memory, two minutes at the default refresh interval
+pub const MEMORY_HISTORY_LEN: usize = 120;
+
+#[derive(Debug, Clone)]
+pub struct MemoryHistory {
+    samples: RingBuffer<(Instant, u64)>,
+}
+
+impl MemoryHistory {
+    pub fn new() -> MemoryHistory {
+        MemoryHistory {
+            samples: RingBuffer::new(MEMORY_HISTORY_LEN),
+        }
+    }
+
+    pub fn push(&mut self, at: Instant, bytes: u64) {
+        self.samples.push((at, bytes));
+    }
+
+    // bytes per minute from a least squares fit over the window, so a steady climb stands
+    // out from a single spike, None until there are a few samples to fit
+    pub fn growth_per_min(&self) -> Option<f32> {
+        if self.samples.len() < 3 {
+            return None;
+        }
+
+        let (start, _) = *self.samples.iter().next()?;
+        let points = self
+            .samples
+            .iter()
+            .map(|(at, bytes)| (at.duration_since(start).as_secs_f64(), *bytes as f64))
+            .collect::<Vec<(f64, f64)>>();
+
+        let n = points.len() as f64;
+        let mean_t = points.iter().map(|(t, _)| t).sum::<f64>() / n;
+        let mean_b = points.iter().map(|(_, b)| b).sum::<f64>() / n;
+
+        let covariance = points
+            .iter()
+            .map(|(t, b)| (t - mean_t) * (b - mean_b))
+            .sum::<f64>();
+        let variance = points.iter().map(|(t, _)| (t - mean_t).powi(2)).sum::<f64>();
+
+        (variance > 0.0).then(|| (covariance / variance * 60.0) as f32)
+    }
+}
+
+// clock speed samples of each core, a minute at the default refresh interval
+pub const CLOCK_HISTORY_LEN: usize = 60;
+
+// a core running this far below the fastest it's been in the window is being held back
+const THROTTLE_FRACTION: f32 = 0.8;
+// idle cores clock down to save power, only a busy one running slow counts
+const THROTTLE_MIN_USAGE: f32 = 50.0;
+
+#[derive(Debug, Clone, Default)]
+pub struct ClockHistory {
+    cores: Vec<RingBuffer<f32>>,
+    // the latest clock speed and usage of each core
+    latest: Vec<(f32, f32)>,
+}
+
+impl ClockHistory {
+    pub fn push(&mut self, cpus: &[Cpu]) {
+        self.cores.resize_with(cpus.len(), || RingBuffer::new(CLOCK_HISTORY_LEN));
+
+        for (history, cpu) in self.cores.iter_mut().zip(cpus) {
+            history.push(cpu.clock_speed);
+        }
+
+        self.latest = cpus.iter().map(|cpu| (cpu.clock_speed, cpu.usage)).collect();
+    }
+
+    // (core, the fastest it's run in the window, its speed now) for each busy core that's
+    // well below its recent best, which is usually the heat
+    pub fn throttled_cores(&self) -> Vec<(usize, f32, f32)> {
+        self.cores
+            .iter()
+            .zip(&self.latest)
+            .enumerate()
+            .filter_map(|(core, (history, &(current, usage)))| {
+                let baseline = history.iter().copied().fold(0.0, f32::max);
+
+                (usage >= THROTTLE_MIN_USAGE
+                    && current > 0.0
+                    && current < baseline * THROTTLE_FRACTION)
+                    .then_some((core, baseline, current))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::json;
+
+    #[test]
+    fn ring_buffer_round_trips_through_json_in_order() {
+        let mut buffer = RingBuffer::new(4);
+
+        for rate in [1u64, 2, 3, 4, 5, 6] {
+            buffer.push(rate);
+        }
+
+        let text = buffer.to_json().to_string();
+        let read = RingBuffer::from_json(&json::parse(&text).unwrap(), 4, |rate| {
+            rate.as_f64().map(|r| r as u64)
+        });
+
+        assert_eq!(read.iter().copied().collect::<Vec<u64>>(), [3, 4, 5, 6]);
+        assert!(read.iter().eq(buffer.iter()));
+    }
+
+    #[test]
+    fn a_smaller_capacity_keeps_the_newest() {
+        let value = json::parse("[1, 2, 3, 4, 5]").unwrap();
+        let read = RingBuffer::from_json(&value, 2, |rate| rate.as_f64().map(|r| r as u64));
+
+        assert_eq!(read.iter().copied().collect::<Vec<u64>>(), [4, 5]);
+    }
+
+    fn cpu(clock_speed: f32, usage: f32) -> Cpu {
+        Cpu {
+            name: String::new(),
+            usage,
+            clock_speed,
+            vendor: String::new(),
+            brand: String::new(),
+            temperature: None,
+            steal: None,
+        }
+    }
+
+    #[test]
+    fn busy_cores_running_well_below_their_best_are_throttled() {
+        let mut history = ClockHistory::default();
+
+        history.push(&[cpu(3000.0, 90.0), cpu(3000.0, 10.0), cpu(3000.0, 90.0)]);
+        history.push(&[cpu(3000.0, 90.0), cpu(3000.0, 10.0), cpu(3000.0, 90.0)]);
+        assert!(history.throttled_cores().is_empty());
+
+        // the first busy core drops past 80% of its best, the idle one drops just as far
+        // but only to save power, and the last busy one dips a little
+        history.push(&[cpu(2000.0, 95.0), cpu(1200.0, 10.0), cpu(2500.0, 95.0)]);
+
+        assert_eq!(history.throttled_cores(), [(0, 3000.0, 2000.0)]);
+    }
+}
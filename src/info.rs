@@ -1,20 +1,175 @@
-use std::{path::Path, time::SystemTime};
+use std::{
+    collections::{HashMap, HashSet},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
 
-use sysinfo::{self, Networks};
+use sysinfo::{self, Networks, Pid};
 
+use crate::history::MemoryHistory;
 
+pub mod platforms;
+
+
+// fully owned, so it can be cloned freely into the process table's rows, the detail view
+// and baseline comparisons, which keeps it cheap to copy only while no field holds more
+// than a handful of values
 #[derive(Debug, Clone)]
 pub struct ProcessData {
     pub pid: u32,
     pub name: String,
     pub exe: String,
+    // the executable was deleted or replaced while it runs, usually by an upgrade, so it
+    // needs restarting to pick up the new one. only known on linux
+    pub exe_deleted: bool,
+    pub cwd: Option<String>,
     pub state: String,
+    // the owner's id, matched against User::uid for a name
+    pub uid: Option<String>,
 
     pub ram: u64,
     pub virtual_memory: u64,
     pub total_time: f32,
     pub start_time: f32,
     pub cpu_usage: f32,
+    // time spent runnable but waiting for a cpu, which is where steal lands since the
+    // kernel keeps no per-process steal figure, from /proc/<pid>/schedstat on linux
+    pub cpu_steal_ms: Option<u64>,
+    pub nice: i32,
+    // SCHED_OTHER, SCHED_FIFO, ... without the prefix, only known on linux
+    pub sched_policy: Option<&'static str>,
+    pub parent_pid: Option<u32>,
+    // the owning process when this entry is one of its threads, only known on linux
+    pub thread_of: Option<u32>,
+    // filled in by SystemInfo::compute_children
+    pub children_pids: Vec<u32>,
+    // bytes per minute over the last couple of minutes, a steady climb is a likely leak
+    pub memory_growth: Option<f32>,
+    // page faults that had to wait on the disk, since it started and per second since the
+    // last refresh. a process swapping itself in and out has a high rate, only known on linux
+    pub major_faults: Option<u64>,
+    pub major_fault_rate: Option<f32>,
+    // counting the main one, only known on linux
+    pub threads: Option<u64>,
+}
+
+impl ProcessData {
+    pub fn process_state(&self) -> ProcessState {
+        ProcessState::from_status(&self.state)
+    }
+
+    // None where sysinfo couldn't read it, it fills in "not_found"
+    fn exe_path(&self) -> Option<&Path> {
+        Some(Path::new(&self.exe)).filter(|_| !self.exe.is_empty() && self.exe != "not_found")
+    }
+
+    // the executable's file name, or the process name when it isn't known
+    pub fn exe_basename(&self) -> &str {
+        self.exe_path()
+            .and_then(Path::file_name)
+            .and_then(|name| name.to_str())
+            .unwrap_or(&self.name)
+    }
+
+    pub fn exe_dir(&self) -> Option<&Path> {
+        self.exe_path()?.parent()
+    }
+
+    // installed by the system rather than the user
+    pub fn is_system_process(&self) -> bool {
+        self.exe_dir()
+            .is_some_and(|dir| ["/usr", "/bin", "/sbin"].iter().any(|s| dir.starts_with(s)))
+    }
+
+    // kernel threads have no executable, and on linux they're all started by kthreadd
+    pub fn is_kernel_thread(&self) -> bool {
+        self.exe_path().is_none() && (self.pid == 2 || self.parent_pid == Some(2))
+    }
+}
+
+// a process with nothing known about it but its pid and name, for building lists in tests
+#[cfg(test)]
+impl ProcessData {
+    pub fn named(pid: u32, name: &str) -> ProcessData {
+        ProcessData {
+            pid,
+            name: name.to_string(),
+            exe: String::new(),
+            exe_deleted: false,
+            cwd: None,
+            state: "Sleeping".to_string(),
+            uid: None,
+            ram: 0,
+            virtual_memory: 0,
+            total_time: 0.0,
+            start_time: 0.0,
+            cpu_usage: 0.0,
+            cpu_steal_ms: None,
+            nice: 0,
+            sched_policy: None,
+            parent_pid: None,
+            thread_of: None,
+            children_pids: vec![],
+            memory_growth: None,
+            major_faults: None,
+            major_fault_rate: None,
+            threads: None,
+        }
+    }
+}
+
+// `state` normalised across platforms, from the status strings sysinfo prints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProcessState {
+    Running,
+    Sleeping,
+    DiskSleep,
+    Zombie,
+    Stopped,
+    Idle,
+    Unknown,
+}
+
+impl ProcessState {
+    pub fn from_status(status: &str) -> ProcessState {
+        match status {
+            "Runnable" => ProcessState::Running,
+            // freebsd's waiting on a lock is a sleep all the same
+            "Sleeping" | "LockBlocked" => ProcessState::Sleeping,
+            "UninterruptibleDiskSleep" => ProcessState::DiskSleep,
+            "Zombie" | "Dead" => ProcessState::Zombie,
+            "Stopped" | "Tracing" => ProcessState::Stopped,
+            "Idle" => ProcessState::Idle,
+            _ => ProcessState::Unknown,
+        }
+    }
+
+    // the letter ps and htop show
+    pub fn code(self) -> &'static str {
+        match self {
+            ProcessState::Running => "R",
+            ProcessState::Sleeping => "S",
+            ProcessState::DiskSleep => "D",
+            ProcessState::Zombie => "Z",
+            ProcessState::Stopped => "T",
+            ProcessState::Idle => "I",
+            ProcessState::Unknown => "?",
+        }
+    }
+
+    pub fn word(self) -> &'static str {
+        match self {
+            ProcessState::Running => "running",
+            ProcessState::Sleeping => "sleeping",
+            ProcessState::DiskSleep => "disk sleep",
+            ProcessState::Zombie => "zombie",
+            ProcessState::Stopped => "stopped",
+            ProcessState::Idle => "idle",
+            ProcessState::Unknown => "unknown",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -22,6 +177,27 @@ pub struct User {
     pub name: String,
     pub uid: String,
     pub groups: Vec<String>,
+    // sysinfo doesn't read these, they come from /etc/passwd on linux
+    pub home_dir: Option<PathBuf>,
+    pub shell: Option<String>,
+}
+
+impl User {
+    // "zsh" from "/usr/bin/zsh"
+    pub fn shell_name(&self) -> Option<&str> {
+        self.shell.as_deref()?.rsplit('/').next().filter(|s| !s.is_empty())
+    }
+}
+
+// what the processes of one owner add up to, see SystemInfo::user_breakdown
+#[derive(Debug, Clone)]
+pub struct UserUsage {
+    // the uid where it doesn't resolve to a name
+    pub name: String,
+    pub uid: Option<String>,
+    pub cpu_usage: f32,
+    pub ram: u64,
+    pub process_count: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +207,85 @@ pub struct SystemSpec {
     pub kernel: String,
     pub uptime: String,
     pub users: Vec<User>,
+    pub architecture: String,
+    // an upgrade left something that only takes effect after a reboot, only known on linux
+    pub pending_reboot: bool,
+    // None until the specs are first refreshed
+    pub environment: Option<Environment>,
+}
+
+// what pidwatch is running under, a container or wsl before the init system
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Environment {
+    Kubernetes,
+    Docker,
+    Podman,
+    Lxc,
+    Wsl,
+    Systemd,
+    // pid 1's name, openrc, runit, a shell...
+    Init(String),
+    Native,
+}
+
+impl Environment {
+    // a handful of files in /, /proc and /run, only on linux
+    #[cfg(target_os = "linux")]
+    fn detect() -> Environment {
+        let read = |path: &str| std::fs::read_to_string(path).unwrap_or_default();
+        let exists = |path: &str| Path::new(path).exists();
+
+        // cgroup v1 names the runtime in the paths, v2 in a container is just "0::/"
+        let cgroup = read("/proc/1/cgroup");
+        let os_release = read("/proc/sys/kernel/osrelease").to_lowercase();
+        let init = read("/proc/1/comm").trim().to_string();
+
+        if std::env::var_os("KUBERNETES_SERVICE_HOST").is_some()
+            || exists("/var/run/secrets/kubernetes.io")
+            || cgroup.contains("kubepods")
+        {
+            Environment::Kubernetes
+        } else if exists("/.dockerenv") || cgroup.contains("docker") {
+            Environment::Docker
+        } else if exists("/run/.containerenv") {
+            Environment::Podman
+        } else if cgroup.contains("lxc") || read("/proc/1/environ").contains("container=lxc") {
+            Environment::Lxc
+        } else if os_release.contains("microsoft") || os_release.contains("wsl") {
+            Environment::Wsl
+        } else if init == "systemd" {
+            Environment::Systemd
+        } else if !init.is_empty() && init != "init" {
+            Environment::Init(init)
+        } else {
+            Environment::Native
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn detect() -> Environment {
+        Environment::Native
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            Environment::Kubernetes => "Kubernetes pod",
+            Environment::Docker => "Docker container",
+            Environment::Podman => "Podman container",
+            Environment::Lxc => "LXC container",
+            Environment::Wsl => "WSL",
+            Environment::Systemd => "systemd",
+            Environment::Init(name) => name,
+            Environment::Native => "native",
+        }
+    }
+}
+
+impl SystemSpec {
+    pub fn is_64bit(&self) -> bool {
+        // x86_64, aarch64, ppc64le, riscv64, ... and s390x which doesn't say so
+        self.architecture.contains("64") || self.architecture == "s390x"
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -43,6 +298,32 @@ pub struct  Disk {
     pub percent: f32,
     pub fs_type: String,
     pub is_removable: bool,
+    // bytes since boot, from /proc/diskstats on linux and 0 elsewhere
+    pub total_read: u64,
+    pub total_written: u64,
+    // bytes per second since the previous refresh, 0 on the first one
+    pub read_per_sec: u64,
+    pub write_per_sec: u64,
+    // from statvfs on unix, None where the filesystem doesn't have a fixed number of them
+    pub inode_used: Option<u64>,
+    pub inode_total: Option<u64>,
+}
+
+// space or inodes used past this share
+const DISK_NEARLY_FULL_PERCENT: f32 = 90.0;
+
+impl Disk {
+    pub fn inode_percent(&self) -> Option<f32> {
+        let total = self.inode_total.filter(|t| *t > 0)?;
+
+        Some(self.inode_used? as f32 / total as f32 * 100.0)
+    }
+
+    // a disk can run out of inodes with plenty of bytes free, lots of small files do it
+    pub fn is_nearly_full(&self) -> bool {
+        self.percent >= DISK_NEARLY_FULL_PERCENT
+            || self.inode_percent().is_some_and(|p| p >= DISK_NEARLY_FULL_PERCENT)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -51,6 +332,26 @@ pub struct Cpu {
     pub usage: f32,
     pub clock_speed: f32,
     pub vendor: String,
+    // the model, e.g. "Apple M2 Pro", where name is just "cpu0"
+    pub brand: String,
+    // from the sensor of the physical core this one runs on, where there is one
+    pub temperature: Option<f32>,
+    // like SystemData::cpu_steal, for this core alone
+    pub steal: Option<f32>,
+}
+
+// everything known about one core, for the cpu panel
+#[derive(Debug, Clone)]
+pub struct CoreSummary {
+    pub core_id: usize,
+    pub usage: f32,
+    // many vms and apple silicon report 0 and an empty vendor, those are None
+    pub clock_speed: Option<f32>,
+    pub temperature: Option<f32>,
+    pub brand: String,
+    pub vendor: Option<String>,
+    // 0 where it isn't known
+    pub steal: f32,
 }
 
 #[derive(Debug, Clone)]
@@ -61,17 +362,252 @@ pub struct  Network {
     pub total_recv: u64,
     pub total_packets_sent: u64,
     pub total_packets_recv: u64,
+    pub total_errors_sent: u64,
+    pub total_errors_recv: u64,
+    // packets the kernel threw away, only known on linux
+    pub total_drops_sent: u64,
+    pub total_drops_recv: u64,
+    // bytes per second since the previous refresh, 0 on the first one
+    pub sent_per_sec: u64,
+    pub recv_per_sec: u64,
+    pub ipv4_addrs: Vec<Ipv4Addr>,
+    pub ipv6_addrs: Vec<Ipv6Addr>,
+    pub kind: InterfaceKind,
+}
+
+// what's behind an interface, from sysfs on linux and its name elsewhere
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterfaceKind {
+    Ethernet,
+    Wifi,
+    Loopback,
+    // bridges, veth pairs and the like, with no hardware of their own
+    Virtual,
+    Vpn,
+}
+
+impl InterfaceKind {
+    // the usual names, anything unrecognised is taken to be a wired nic
+    pub fn from_name(name: &str) -> InterfaceKind {
+        const VPN: [&str; 8] = ["tun", "tap", "wg", "utun", "ppp", "ipsec", "tailscale", "zt"];
+        const WIFI: [&str; 3] = ["wl", "wifi", "ath"];
+        const VIRTUAL: [&str; 13] = [
+            "docker", "br-", "veth", "virbr", "vmnet", "vboxnet", "lxc", "lxd", "cni", "flannel",
+            "cali", "ifb", "dummy",
+        ];
+
+        let starts = |prefixes: &[&str]| prefixes.iter().any(|p| name.starts_with(p));
+
+        if name == "lo" || name == "lo0" {
+            InterfaceKind::Loopback
+        } else if starts(&VPN) {
+            InterfaceKind::Vpn
+        } else if starts(&WIFI) {
+            InterfaceKind::Wifi
+        } else if starts(&VIRTUAL) {
+            InterfaceKind::Virtual
+        } else {
+            InterfaceKind::Ethernet
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            InterfaceKind::Ethernet => "ethernet",
+            InterfaceKind::Wifi => "wifi",
+            InterfaceKind::Loopback => "loopback",
+            InterfaceKind::Virtual => "virtual",
+            InterfaceKind::Vpn => "vpn",
+        }
+    }
+}
+
+impl Network {
+    pub fn primary_ipv4(&self) -> Option<Ipv4Addr> {
+        self.ipv4_addrs.iter().copied().find(|a| !a.is_loopback())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressScope {
+    Loopback,
+    LinkLocal,
+    // rfc 1918 and unique local addresses
+    Private,
+    Public,
+}
+
+impl AddressScope {
+    pub fn of(address: IpAddr) -> AddressScope {
+        match address {
+            IpAddr::V4(a) if a.is_loopback() => AddressScope::Loopback,
+            IpAddr::V4(a) if a.is_link_local() => AddressScope::LinkLocal,
+            IpAddr::V4(a) if a.is_private() => AddressScope::Private,
+            IpAddr::V6(a) if a.is_loopback() => AddressScope::Loopback,
+            IpAddr::V6(a) if a.is_unicast_link_local() => AddressScope::LinkLocal,
+            IpAddr::V6(a) if a.is_unique_local() => AddressScope::Private,
+            _ => AddressScope::Public,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct SystemData {
     pub cpus: Vec<Cpu>,
     pub memory: u64,
+    // what new processes could get without swapping, free memory plus the cache that can
+    // be dropped (MemAvailable on linux)
+    pub available_memory: u64,
     pub swap: u64,
     pub disks: Vec<Disk>,
     pub total_memory: u64,
     pub total_swap: u64,
     pub networks: Vec<Network>,
+    // share of cpu time the hypervisor took since the last refresh, only known on linux
+    pub cpu_steal: Option<f32>,
+    // usage is measured between two samples, until there are two every figure reads 0
+    pub cpu_measured: bool,
+}
+
+// available memory below this share of the total is worth noticing
+const LOW_AVAILABLE_FRACTION: f64 = 0.1;
+
+impl SystemData {
+    // the ram of every process, thread entries share their owner's and are left out. it
+    // falls short of `memory` by what the kernel, its buffers and caches hold
+    pub fn memory_used_by_processes(processes: &[ProcessData]) -> u64 {
+        processes
+            .iter()
+            .filter(|p| p.thread_of.is_none())
+            .map(|p| p.ram)
+            .sum()
+    }
+
+    pub fn average_cpu_usage(&self) -> f32 {
+        if self.cpus.is_empty() {
+            return 0.0;
+        }
+
+        self.cpus.iter().map(|c| c.usage).sum::<f32>() / self.cpus.len() as f32
+    }
+
+    // None when there are no cpus or they're a mix, like performance and efficiency cores
+    pub fn cpu_brand(&self) -> Option<String> {
+        let brand = &self.cpus.first()?.brand;
+
+        self.cpus
+            .iter()
+            .all(|c| c.brand == *brand)
+            .then(|| brand.clone())
+    }
+
+    pub fn per_core_summary(&self) -> Vec<CoreSummary> {
+        self.cpus
+            .iter()
+            .enumerate()
+            .map(|(core_id, cpu)| CoreSummary {
+                core_id,
+                usage: cpu.usage,
+                clock_speed: (cpu.clock_speed > 0.0).then_some(cpu.clock_speed),
+                temperature: cpu.temperature,
+                brand: cpu.brand.clone(),
+                vendor: Some(cpu.vendor.trim())
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string),
+                steal: cpu.steal.unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    // errors and drops both ways since boot, summed over every interface
+    pub fn total_network_errors(&self) -> u64 {
+        self.networks
+            .iter()
+            .map(|n| {
+                n.total_errors_sent + n.total_errors_recv + n.total_drops_sent + n.total_drops_recv
+            })
+            .sum()
+    }
+
+    // errors and drops per second since `previous`, an interface that's gone or reset its
+    // counters doesn't count
+    pub fn network_error_rate(&self, previous: &SystemData, elapsed_secs: f64) -> f64 {
+        if elapsed_secs <= 0.0 {
+            return 0.0;
+        }
+
+        self.total_network_errors()
+            .saturating_sub(previous.total_network_errors()) as f64
+            / elapsed_secs
+    }
+
+    // interfaces that aren't in `order` yet go on the end, so the panel keeps its order
+    // and one that appears later, or after an empty list at startup, still shows
+    pub fn extend_network_order(&self, order: &mut Vec<String>) {
+        for network in &self.networks {
+            if !order.contains(&network.name) {
+                order.push(network.name.clone());
+            }
+        }
+    }
+
+    pub fn is_available_memory_low(&self) -> bool {
+        (self.available_memory as f64) < self.total_memory as f64 * LOW_AVAILABLE_FRACTION
+    }
+
+    pub fn memory_percent(&self) -> f32 {
+        if self.total_memory == 0 {
+            return 0.0;
+        }
+
+        self.memory as f32 / self.total_memory as f32 * 100.0
+    }
+}
+
+// snapshots are cloned into the ui from the collector's thread, so none of these may
+// borrow anything, checked at compile time
+const _: () = {
+    const fn assert_clone<T: Clone + Send + 'static>() {}
+
+    assert_clone::<ProcessData>();
+    assert_clone::<User>();
+    assert_clone::<UserUsage>();
+    assert_clone::<SystemSpec>();
+    assert_clone::<Disk>();
+    assert_clone::<Cpu>();
+    assert_clone::<CoreSummary>();
+    assert_clone::<Network>();
+    assert_clone::<SystemData>();
+    assert_clone::<SystemInfo>();
+};
+
+// the parts of SystemInfo that can be refreshed on their own, each at its own rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Subsystem {
+    Cpu,
+    Memory,
+    Processes,
+    Networks,
+    Disks,
+    Specs,
+    Users,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 7] = [
+        Subsystem::Cpu,
+        Subsystem::Memory,
+        Subsystem::Networks,
+        Subsystem::Disks,
+        Subsystem::Processes,
+        Subsystem::Specs,
+        Subsystem::Users,
+    ];
+
+    // walking every interface and mount, skipped in PerformanceMode::Low
+    pub fn expensive(self) -> bool {
+        matches!(self, Subsystem::Networks | Subsystem::Disks)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -79,6 +615,486 @@ pub struct SystemInfo {
     pub usage: SystemData,
     pub processes: Vec<ProcessData>,
     pub spec: SystemSpec,
+    // kept between refreshes so cpu usage is measured since the last one, and so
+    // single processes can be refreshed on their own
+    system: Arc<Mutex<sysinfo::System>>,
+    // like SystemData::cpu_measured, for the cpu usage of processes
+    pub processes_measured: bool,
+    // whether `system` has sampled cpu and process times at least once
+    cpus_sampled: bool,
+    processes_sampled: bool,
+    // per pid, shared between clones so the ui's copies don't each carry the whole window
+    memory_history: Arc<Mutex<HashMap<u32, MemoryHistory>>>,
+    // temperature sensors, read along with the cpus
+    components: Arc<Mutex<sysinfo::Components>>,
+    networks_sampled_at: Option<Instant>,
+    disks_sampled_at: Option<Instant>,
+    processes_sampled_at: Option<Instant>,
+    reboot_checked_at: Option<Instant>,
+    // the (steal, total) jiffies steal was last measured from, the whole machine first
+    // and then each core, empty where they can't be read
+    steal_sample: Vec<(u64, u64)>,
+}
+
+// (read, written) bytes per block device since boot, which sysinfo doesn't read. the
+// sector counts in /proc/diskstats are always in 512 byte units
+#[cfg(target_os = "linux")]
+fn read_disk_io() -> HashMap<String, (u64, u64)> {
+    let Ok(stats) = std::fs::read_to_string("/proc/diskstats") else {
+        return HashMap::new();
+    };
+
+    stats
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            let read = fields.get(5)?.parse::<u64>().ok()?;
+            let written = fields.get(9)?.parse::<u64>().ok()?;
+
+            Some((fields.get(2)?.to_string(), (read * 512, written * 512)))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_disk_io() -> HashMap<String, (u64, u64)> {
+    HashMap::new()
+}
+
+// (sent, received) packets dropped, which sysinfo doesn't read
+#[cfg(target_os = "linux")]
+fn read_interface_drops(interface: &str) -> (u64, u64) {
+    let counter = |name: &str| {
+        std::fs::read_to_string(format!("/sys/class/net/{}/statistics/{}", interface, name))
+            .ok()
+            .and_then(|c| c.trim().parse().ok())
+            .unwrap_or_default()
+    };
+
+    (counter("tx_dropped"), counter("rx_dropped"))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_interface_drops(_interface: &str) -> (u64, u64) {
+    (0, 0)
+}
+
+// (used, total) inodes of the filesystem mounted at `mount`
+#[cfg(unix)]
+fn read_inodes(mount: &str) -> Option<(u64, u64)> {
+    use std::{ffi::CString, mem::MaybeUninit};
+
+    let path = CString::new(mount).ok()?;
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+
+    if unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return None;
+    }
+
+    let stat = unsafe { stat.assume_init() };
+
+    // fsfilcnt_t is only 32 bits on some platforms
+    #[allow(clippy::unnecessary_cast)]
+    let (total, free) = (stat.f_files as u64, stat.f_ffree as u64);
+
+    // btrfs and the like hand them out as needed and report 0
+    (total > 0).then(|| (total.saturating_sub(free), total))
+}
+
+#[cfg(not(unix))]
+fn read_inodes(_mount: &str) -> Option<(u64, u64)> {
+    None
+}
+
+// the link type in /sys/class/net/<interface>/type is 772 for loopback and 65534 for
+// interfaces with no link layer, like wireguard. hardware has a device link
+#[cfg(target_os = "linux")]
+fn interface_kind(interface: &str) -> InterfaceKind {
+    let dir = Path::new("/sys/class/net").join(interface);
+
+    if !dir.exists() {
+        return InterfaceKind::from_name(interface);
+    }
+
+    let link_type = std::fs::read_to_string(dir.join("type"))
+        .ok()
+        .and_then(|t| t.trim().parse::<u32>().ok());
+
+    if link_type == Some(772) {
+        InterfaceKind::Loopback
+    } else if dir.join("wireless").exists() || dir.join("phy80211").exists() {
+        InterfaceKind::Wifi
+    } else if dir.join("tun_flags").exists() || link_type == Some(65534) {
+        InterfaceKind::Vpn
+    } else if dir.join("device").exists() {
+        InterfaceKind::Ethernet
+    } else {
+        match InterfaceKind::from_name(interface) {
+            InterfaceKind::Ethernet => InterfaceKind::Virtual,
+            kind => kind,
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn interface_kind(interface: &str) -> InterfaceKind {
+    InterfaceKind::from_name(interface)
+}
+
+// fields of /proc/<pid>/stat from the third (state) onwards, comm is skipped
+// since it can contain spaces and parentheses itself
+#[cfg(target_os = "linux")]
+fn read_proc_stat(pid: u32) -> Option<Vec<String>> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let (_, rest) = stat.rsplit_once(')')?;
+
+    Some(rest.split_whitespace().map(|f| f.to_string()).collect())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_proc_stat(_pid: u32) -> Option<Vec<String>> {
+    None
+}
+
+// how often the reboot markers are looked for, they only appear after an upgrade
+const REBOOT_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// the markers left by debian and ubuntu's package hooks, and by systemd when it's been
+// asked to boot into another entry
+#[cfg(target_os = "linux")]
+const REBOOT_MARKERS: &[&str] = &[
+    "/var/run/reboot-required",
+    "/var/run/reboot-required.pkgs",
+    "/run/systemd/reboot-to-boot-loader-entry",
+];
+
+#[cfg(not(target_os = "linux"))]
+const REBOOT_MARKERS: &[&str] = &[];
+
+fn reboot_required<P: AsRef<Path>>(markers: &[P]) -> bool {
+    markers.iter().any(|marker| marker.as_ref().exists())
+}
+
+// the kernel appends " (deleted)" to the /proc/<pid>/exe link once the file is gone
+#[cfg(target_os = "linux")]
+fn is_deleted_exe(target: &Path) -> bool {
+    target.to_string_lossy().ends_with(" (deleted)")
+}
+
+#[cfg(target_os = "linux")]
+fn read_exe_deleted(pid: u32) -> bool {
+    std::fs::read_link(format!("/proc/{}/exe", pid)).is_ok_and(|target| is_deleted_exe(&target))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_exe_deleted(_pid: u32) -> bool {
+    false
+}
+
+// the run queue wait in /proc/<pid>/schedstat (time on cpu, wait, timeslices), in ms
+#[cfg(target_os = "linux")]
+fn read_run_delay_ms(pid: u32) -> Option<u64> {
+    let schedstat = std::fs::read_to_string(format!("/proc/{}/schedstat", pid)).ok()?;
+    let wait_ns = schedstat.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+
+    Some(wait_ns / 1_000_000)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_run_delay_ms(_pid: u32) -> Option<u64> {
+    None
+}
+
+// uid to home directory and login shell, from lines like
+// "alice:x:1000:1000:Alice:/home/alice:/usr/bin/zsh"
+#[cfg(target_os = "linux")]
+fn parse_passwd(passwd: &str) -> HashMap<String, (PathBuf, String)> {
+    passwd
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields = line.split(':').collect::<Vec<&str>>();
+
+            match fields[..] {
+                [_, _, uid, _, _, home, shell] => {
+                    Some((uid.to_string(), (PathBuf::from(home), shell.to_string())))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn read_passwd() -> HashMap<String, (PathBuf, String)> {
+    std::fs::read_to_string("/etc/passwd")
+        .map(|passwd| parse_passwd(&passwd))
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_passwd() -> HashMap<String, (PathBuf, String)> {
+    HashMap::new()
+}
+
+// (steal, total) jiffies from the cpu lines of /proc/stat, the aggregate line and then
+// one per core, guest time is already counted in user so only the first eight fields
+// add up to the total
+#[cfg(target_os = "linux")]
+fn read_cpu_steal() -> Vec<(u64, u64)> {
+    let Ok(stat) = std::fs::read_to_string("/proc/stat") else {
+        return vec![];
+    };
+
+    stat.lines()
+        .take_while(|l| l.starts_with("cpu"))
+        .map_while(|line| {
+            let fields = line
+                .split_whitespace()
+                .skip(1)
+                .take(8)
+                .map(|f| f.parse::<u64>().ok())
+                .collect::<Option<Vec<u64>>>()?;
+
+            Some((*fields.get(7)?, fields.iter().sum()))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_cpu_steal() -> Vec<(u64, u64)> {
+    vec![]
+}
+
+// (interface, address) for every local ipv4 address. the kernel only lists them in the
+// fib trie, which doesn't say which interface they're on, so they're matched up with the
+// most specific route that covers them
+#[cfg(target_os = "linux")]
+fn read_ipv4_addresses() -> Vec<(String, Ipv4Addr)> {
+    let Ok(trie) = std::fs::read_to_string("/proc/net/fib_trie") else {
+        return vec![];
+    };
+
+    // "|-- 192.0.2.2" followed by "/32 host LOCAL", the main and local tables both list it
+    let mut addresses = vec![];
+    let mut last = None;
+
+    for line in trie.lines().map(str::trim) {
+        if let Some(address) = line.strip_prefix("|-- ") {
+            last = address.parse::<Ipv4Addr>().ok();
+        } else if line.starts_with("/32 host LOCAL") {
+            if let Some(address) = last.filter(|a| !addresses.contains(a)) {
+                addresses.push(address);
+            }
+        }
+    }
+
+    // Iface Destination Gateway Flags RefCnt Use Metric Mask, in native endian hex
+    let hex = |field: &str| {
+        u32::from_str_radix(field, 16)
+            .ok()
+            .map(|word| u32::from(Ipv4Addr::from(word.to_ne_bytes())))
+    };
+
+    let routes = std::fs::read_to_string("/proc/net/route").unwrap_or_default();
+    let routes = routes
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            Some((*fields.first()?, hex(fields.get(1)?)?, hex(fields.get(7)?)?))
+        })
+        .filter(|(_, _, mask)| *mask != 0)
+        .collect::<Vec<(&str, u32, u32)>>();
+
+    addresses
+        .into_iter()
+        .filter_map(|address| {
+            if address.is_loopback() {
+                return Some(("lo".to_string(), address));
+            }
+
+            let (interface, _, _) = routes
+                .iter()
+                .filter(|(_, destination, mask)| u32::from(address) & mask == *destination)
+                .max_by_key(|(_, _, mask)| mask.count_ones())?;
+
+            Some((interface.to_string(), address))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_ipv4_addresses() -> Vec<(String, Ipv4Addr)> {
+    vec![]
+}
+
+// address index prefix scope flags name, the address as 32 hex digits
+#[cfg(target_os = "linux")]
+fn read_ipv6_addresses() -> Vec<(String, Ipv6Addr)> {
+    let Ok(table) = std::fs::read_to_string("/proc/net/if_inet6") else {
+        return vec![];
+    };
+
+    table
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            let address = u128::from_str_radix(fields.first()?, 16).ok()?;
+
+            Some((fields.get(5)?.to_string(), Ipv6Addr::from(address)))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_ipv6_addresses() -> Vec<(String, Ipv6Addr)> {
+    vec![]
+}
+
+// share of the time between two samples that was stolen
+fn steal_percent(old: (u64, u64), new: (u64, u64)) -> Option<f32> {
+    let (old_steal, old_total) = old;
+    let (steal, total) = new;
+
+    (total > old_total)
+        .then(|| steal.saturating_sub(old_steal) as f32 / (total - old_total) as f32 * 100.0)
+}
+
+// sensors are per physical core, hyperthreads share one
+#[cfg(target_os = "linux")]
+fn physical_core_id(cpu: usize) -> Option<usize> {
+    std::fs::read_to_string(format!(
+        "/sys/devices/system/cpu/cpu{}/topology/core_id",
+        cpu
+    ))
+    .ok()?
+    .trim()
+    .parse()
+    .ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn physical_core_id(cpu: usize) -> Option<usize> {
+    Some(cpu)
+}
+
+fn process_data(pid: Pid, process: &sysinfo::Process, cpu_count: usize) -> ProcessData {
+    let stat = read_proc_stat(pid.as_u32()).unwrap_or_default();
+
+    let current_time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs() as f32;
+
+    let total_time = current_time - process.start_time() as f32;
+
+    ProcessData {
+        pid: pid.as_u32(),
+        name: process.name().to_string(),
+        exe: process.exe().unwrap_or(Path::new("not_found")).as_os_str().to_str().unwrap_or_default().to_string(),
+        exe_deleted: read_exe_deleted(pid.as_u32()),
+        cwd: process.cwd().map(|p| p.to_string_lossy().to_string()),
+        state: process.status().to_string(),
+        uid: process.user_id().map(|uid| uid.to_string()),
+        ram: process.memory(),
+        virtual_memory: process.virtual_memory(),
+        total_time,
+        start_time: process.start_time() as f32,
+        // divide by number of cpus to get percentage
+        cpu_usage: process.cpu_usage() / cpu_count as f32,
+        cpu_steal_ms: read_run_delay_ms(pid.as_u32()),
+        // majflt is field 12, nice field 19, num_threads field 20 and the policy field 41 of
+        // /proc/<pid>/stat
+        major_faults: stat.get(9).and_then(|f| f.parse().ok()),
+        major_fault_rate: None,
+        threads: stat.get(17).and_then(|f| f.parse().ok()),
+        nice: stat.get(16).and_then(|f| f.parse().ok()).unwrap_or_default(),
+        sched_policy: stat.get(38).and_then(|f| f.parse().ok()).map(sched_policy_name),
+        parent_pid: process.parent().map(|p| p.as_u32()),
+        // kernel threads are processes of their own
+        thread_of: (process.thread_kind() == Some(sysinfo::ThreadKind::Userland))
+            .then(|| process.parent().map(|p| p.as_u32()))
+            .flatten(),
+        children_pids: vec![],
+        memory_growth: None,
+    }
+}
+
+// the SCHED_* constants from sched.h
+fn sched_policy_name(policy: u32) -> &'static str {
+    match policy {
+        0 => "OTHER",
+        1 => "FIFO",
+        2 => "RR",
+        3 => "BATCH",
+        5 => "IDLE",
+        6 => "DEADLINE",
+        _ => "UNKNOWN",
+    }
+}
+
+fn uname_machine() -> Option<String> {
+    let output = std::process::Command::new("uname").arg("-m").output().ok()?;
+    let machine = String::from_utf8(output.stdout).ok()?.trim().to_string();
+
+    (output.status.success() && !machine.is_empty()).then_some(machine)
+}
+
+// ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MemoryPressure {
+    Normal,
+    Elevated,
+    High,
+    Critical,
+}
+
+impl MemoryPressure {
+    pub fn from_percent(percent: f32) -> MemoryPressure {
+        if percent < 60.0 {
+            MemoryPressure::Normal
+        } else if percent < 80.0 {
+            MemoryPressure::Elevated
+        } else if percent <= 90.0 {
+            MemoryPressure::High
+        } else {
+            MemoryPressure::Critical
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            MemoryPressure::Normal => "normal",
+            MemoryPressure::Elevated => "elevated",
+            MemoryPressure::High => "high",
+            MemoryPressure::Critical => "critical",
+        }
+    }
+}
+
+// what goes into SystemInfo::health_score, each as a percentage of the resource in use
+#[derive(Debug, Clone, Copy)]
+pub struct HealthComponents {
+    pub cpu: f32,
+    pub memory: f32,
+    // 0 without any swap
+    pub swap: f32,
+    // the fullest disk
+    pub disk: f32,
+    // errors and drops as a share of the packets every interface has seen since boot
+    pub network_errors: f32,
+}
+
+impl HealthComponents {
+    // 100 - (cpu * 0.3 + memory * 0.3 + swap * 0.2 + disk * 0.1 + network_errors * 0.1),
+    // so 100 with everything idle and 0 with everything at its limit
+    pub fn score(&self) -> f32 {
+        let load = self.cpu * 0.3
+            + self.memory * 0.3
+            + self.swap * 0.2
+            + self.disk * 0.1
+            + self.network_errors * 0.1;
+
+        (100.0 - load).clamp(0.0, 100.0)
+    }
 }
 
 impl SystemInfo {
@@ -87,11 +1103,14 @@ impl SystemInfo {
             usage: SystemData {
                 cpus: vec![],
                 memory: 0,
+                available_memory: 0,
                 swap: 0,
                 disks: vec![],
                 total_memory: 0,
                 total_swap: 0,
                 networks: vec![],
+                cpu_steal: None,
+                cpu_measured: false,
             },
             processes: Vec::new(),
             spec: SystemSpec {
@@ -100,40 +1119,147 @@ impl SystemInfo {
                 kernel: String::new(),
                 uptime: String::new(),
                 users: vec![],
+                architecture: String::new(),
+                pending_reboot: false,
+                environment: None,
             },
+            system: Arc::new(Mutex::new(sysinfo::System::new())),
+            processes_measured: false,
+            cpus_sampled: false,
+            processes_sampled: false,
+            memory_history: Arc::new(Mutex::new(HashMap::new())),
+            components: Arc::new(Mutex::new(sysinfo::Components::new())),
+            networks_sampled_at: None,
+            disks_sampled_at: None,
+            processes_sampled_at: None,
+            reboot_checked_at: None,
+            steal_sample: vec![],
         }
     }
 
     pub fn populate(&mut self) {
-        let mut sys = sysinfo::System::new_all();
+        {
+            let mut sys = self.system.lock().unwrap();
+
+            sys.refresh_all();
+            self.components.lock().unwrap().refresh_list();
+            self.cpus_sampled = true;
+            self.processes_sampled = true;
+
+            // cpu usage is measured between two refreshes
+            std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        }
+
+        for subsystem in Subsystem::ALL {
+            self.populate_subsystem(subsystem);
+        }
+    }
+
+    // refreshes one part of the data on its own, so the parts can be refreshed at
+    // different rates, see collector::Collector
+    pub fn populate_subsystem(&mut self, subsystem: Subsystem) {
+        match subsystem {
+            Subsystem::Cpu => self.refresh_cpus(),
+            Subsystem::Memory => self.refresh_memory(),
+            Subsystem::Processes => self.refresh_processes(),
+            Subsystem::Networks => self.refresh_networks(),
+            Subsystem::Disks => self.refresh_disks(),
+            Subsystem::Specs => self.refresh_specs(),
+            Subsystem::Users => self.refresh_users(),
+        }
+    }
+
+    pub fn refresh_cpus(&mut self) {
+        let mut sys = self.system.lock().unwrap();
+
+        sys.refresh_cpu();
 
-        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        // coretemp labels its sensors "Core <physical id>"
+        let mut components = self.components.lock().unwrap();
+        components.refresh();
 
-        sys.refresh_all();
+        let temperatures = components
+            .iter()
+            .filter_map(|c| {
+                let id = c.label().rsplit_once("Core ")?.1.parse::<usize>().ok()?;
+                Some((id, c.temperature()))
+            })
+            .collect::<HashMap<usize, f32>>();
 
-        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        drop(components);
+
+        let sample = read_cpu_steal();
+
+        // where no time has passed the last figure is kept
+        let steal = |i: usize| match (self.steal_sample.get(i), sample.get(i)) {
+            (Some(&old), Some(&new)) => steal_percent(old, new),
+            _ => None,
+        };
 
         let mut cpus = vec![];
 
-        for cpu in sys.cpus() {
+        for (i, cpu) in sys.cpus().iter().enumerate() {
+            let previous = self.usage.cpus.get(i).and_then(|c| c.steal);
+
             cpus.push(Cpu {
                 name: cpu.name().to_string(),
-                usage: cpu.cpu_usage() as f32,
+                usage: cpu.cpu_usage(),
                 clock_speed: cpu.frequency() as f32,
                 vendor: cpu.vendor_id().to_string(),
+                brand: cpu.brand().trim().to_string(),
+                temperature: physical_core_id(i).and_then(|id| temperatures.get(&id).copied()),
+                // the aggregate line comes first
+                steal: steal(i + 1).or(previous),
             });
         }
 
         self.usage.cpus = cpus;
+        self.usage.cpu_steal = steal(0).or(self.usage.cpu_steal);
+        self.usage.cpu_measured = self.cpus_sampled;
+        self.cpus_sampled = true;
+
+        drop(sys);
+
+        if !sample.is_empty() {
+            self.steal_sample = sample;
+        }
+    }
+
+    pub fn refresh_memory(&mut self) {
+        let mut sys = self.system.lock().unwrap();
+
+        sys.refresh_memory();
 
         self.usage.memory = sys.used_memory();
+        self.usage.available_memory = sys.available_memory();
         self.usage.swap = sys.used_swap();
         self.usage.total_memory = sys.total_memory();
         self.usage.total_swap = sys.total_swap();
+    }
 
+    pub fn refresh_networks(&mut self) {
         let mut networks = vec![];
 
+        let sampled_at = Instant::now();
+        let elapsed = self
+            .networks_sampled_at
+            .map(|t| sampled_at.duration_since(t).as_secs_f64())
+            .filter(|e| *e > 0.0);
+
+        let ipv4_addresses = read_ipv4_addresses();
+        let ipv6_addresses = read_ipv6_addresses();
+
         for (name, network) in Networks::new_with_refreshed_list().iter() {
+            let previous = self.usage.networks.iter().find(|n| n.name == *name);
+            let drops = read_interface_drops(name);
+
+            let per_sec = |total: u64, previous_total: Option<u64>| match (previous_total, elapsed) {
+                (Some(previous_total), Some(elapsed)) => {
+                    (total.saturating_sub(previous_total) as f64 / elapsed) as u64
+                }
+                _ => 0,
+            };
+
             networks.push(Network {
                 name: name.to_string(),
                 mac: network.mac_address().to_string(),
@@ -141,67 +1267,849 @@ impl SystemInfo {
                 total_recv: network.total_received(),
                 total_packets_sent: network.total_packets_transmitted(),
                 total_packets_recv: network.total_packets_received(),
+                total_errors_sent: network.total_errors_on_transmitted(),
+                total_errors_recv: network.total_errors_on_received(),
+                total_drops_sent: drops.0,
+                total_drops_recv: drops.1,
+                sent_per_sec: per_sec(
+                    network.total_transmitted(),
+                    previous.map(|p| p.total_sent),
+                ),
+                recv_per_sec: per_sec(network.total_received(), previous.map(|p| p.total_recv)),
+                ipv4_addrs: ipv4_addresses
+                    .iter()
+                    .filter(|(interface, _)| interface == name)
+                    .map(|(_, address)| *address)
+                    .collect(),
+                ipv6_addrs: ipv6_addresses
+                    .iter()
+                    .filter(|(interface, _)| interface == name)
+                    .map(|(_, address)| *address)
+                    .collect(),
+                kind: interface_kind(name),
             });
         }
 
         self.usage.networks = networks;
+        self.networks_sampled_at = Some(sampled_at);
+    }
 
+    pub fn refresh_disks(&mut self) {
         let mut disks = vec![];
 
+        let sampled_at = Instant::now();
+        let elapsed = self
+            .disks_sampled_at
+            .map(|t| sampled_at.duration_since(t).as_secs_f64())
+            .filter(|e| *e > 0.0);
+
+        let io = read_disk_io();
+
         for disk in &sysinfo::Disks::new_with_refreshed_list() {
+            let name = disk.name().to_str().unwrap_or_default().to_string();
+            let mount = disk.mount_point().to_str().unwrap_or_default().to_string();
+            let previous = self.usage.disks.iter().find(|d| d.mount == mount);
+            let (total_read, total_written) = io
+                .get(name.trim_start_matches("/dev/"))
+                .copied()
+                .unwrap_or_default();
+
+            let per_sec = |total: u64, previous_total: Option<u64>| match (previous_total, elapsed) {
+                (Some(previous_total), Some(elapsed)) => {
+                    (total.saturating_sub(previous_total) as f64 / elapsed) as u64
+                }
+                _ => 0,
+            };
+
+            let inodes = read_inodes(&mount);
+
             disks.push(Disk {
-                name: disk.name().to_str().unwrap_or_default().to_string(),
-                mount: disk.mount_point().to_str().unwrap_or_default().to_string(),
+                name,
                 total: disk.total_space(),
                 used: disk.total_space() - disk.available_space(),
                 free: disk.available_space(),
-                percent: disk.total_space() as f32 / disk.available_space() as f32,
+                percent: if disk.total_space() == 0 {
+                    0.0
+                } else {
+                    (disk.total_space() - disk.available_space()) as f32 / disk.total_space() as f32
+                        * 100.0
+                },
                 fs_type: disk.file_system().to_str().unwrap_or_default().to_string(),
                 is_removable: disk.is_removable(),
+                total_read,
+                total_written,
+                read_per_sec: per_sec(total_read, previous.map(|d| d.total_read)),
+                write_per_sec: per_sec(total_written, previous.map(|d| d.total_written)),
+                inode_used: inodes.map(|(used, _)| used),
+                inode_total: inodes.map(|(_, total)| total),
+                mount,
             });
         }
 
         self.usage.disks = disks;
+        self.disks_sampled_at = Some(sampled_at);
+    }
+
+    pub fn refresh_processes(&mut self) {
+        let mut sys = self.system.lock().unwrap();
+
+        sys.refresh_processes();
 
         let mut processes = vec![];
 
         for (pid, process) in sys.processes() {
-            let current_time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs() as f32;
-
-            let total_time = current_time - process.start_time() as f32;
-            processes.push(
-                ProcessData {
-                    pid: pid.as_u32(),
-                    name: process.name().to_string(),
-                    exe: process.exe().unwrap_or(Path::new("not_found")).as_os_str().to_str().unwrap_or_default().to_string(),
-                    state: process.status().to_string(),
-                    ram: process.memory(),
-                    virtual_memory: process.virtual_memory(),
-                    total_time: total_time,
-                    start_time: process.start_time() as f32,
-                    // divide by number of cpus to get percentage
-                    cpu_usage: process.cpu_usage() as f32 / sys.cpus().iter().count() as f32,
-                }
-            );
+            processes.push(process_data(*pid, process, sys.cpus().len()));
+        }
+
+        drop(sys);
+
+        let now = Instant::now();
+        let mut history = self.memory_history.lock().unwrap();
+
+        let pids = processes.iter().map(|p| p.pid).collect::<HashSet<u32>>();
+        history.retain(|pid, _| pids.contains(pid));
+
+        // threads share their process's memory, which is already counted
+        for process in processes.iter_mut().filter(|p| p.thread_of.is_none()) {
+            let samples = history.entry(process.pid).or_insert_with(MemoryHistory::new);
+
+            samples.push(now, process.ram);
+            process.memory_growth = samples.growth_per_min();
+        }
+
+        drop(history);
+
+        // rates come from the stat already read for each process, there's nothing more to
+        // read. a pid that's been reused by a new process starts again from nothing
+        if let Some(elapsed) = self.processes_sampled_at.map(|at| now.duration_since(at)) {
+            let previous = self
+                .processes
+                .iter()
+                .filter_map(|p| Some((p.pid, (p.start_time, p.major_faults?))))
+                .collect::<HashMap<u32, (f32, u64)>>();
+
+            for process in &mut processes {
+                process.major_fault_rate = match (previous.get(&process.pid), process.major_faults)
+                {
+                    (Some(&(start_time, old)), Some(faults))
+                        if start_time == process.start_time && elapsed.as_secs_f32() > 0.0 =>
+                    {
+                        Some(faults.saturating_sub(old) as f32 / elapsed.as_secs_f32())
+                    }
+                    _ => None,
+                };
+            }
         }
 
         self.processes = processes;
+        self.processes_sampled_at = Some(now);
+        self.processes_measured = self.processes_sampled;
+        self.processes_sampled = true;
 
+        self.compute_children();
+    }
+
+    pub fn refresh_specs(&mut self) {
         self.spec.os = format!("{} {}", sysinfo::System::name().unwrap_or("Unknown".to_string()), sysinfo::System::os_version().unwrap_or_default());
         self.spec.hostname = sysinfo::System::host_name().unwrap_or_default().to_string();
         self.spec.kernel = sysinfo::System::kernel_version().unwrap_or_default().to_string();
         self.spec.uptime = sysinfo::System::uptime().to_string();
 
+        // the architecture can't change while we're running, so only look it up once
+        if self.spec.architecture.is_empty() {
+            self.spec.architecture = sysinfo::System::cpu_arch()
+                .or_else(uname_machine)
+                .unwrap_or_else(|| "unknown".to_string());
+        }
+
+        if self.spec.environment.is_none() {
+            self.spec.environment = Some(Environment::detect());
+        }
+
+        // it's a few file lookups, but there's no need to make them every refresh
+        if self
+            .reboot_checked_at
+            .is_none_or(|at| at.elapsed() >= REBOOT_CHECK_INTERVAL)
+        {
+            self.spec.pending_reboot = reboot_required(REBOOT_MARKERS);
+            self.reboot_checked_at = Some(Instant::now());
+        }
+    }
+
+    pub fn refresh_users(&mut self) {
         let mut users = vec![];
+        let mut passwd = read_passwd();
 
         for user in sysinfo::Users::new_with_refreshed_list().list() {
+            let uid = user.id().to_string();
+            let (home_dir, shell) = passwd.remove(&uid).unzip();
+
             users.push(User {
                 name: user.name().to_string(),
-                uid: user.id().to_string(),
                 groups: user.groups().iter().map(|x| x.name().to_string()).collect(),
+                uid,
+                home_dir,
+                shell,
             });
         }
 
         self.spec.users = users;
     }
-}
\ No newline at end of file
+
+    // much cheaper than populate(), returns false if the process has gone away
+    pub fn refresh_process(&mut self, pid: u32) -> bool {
+        let mut sys = self.system.lock().unwrap();
+        let pid = Pid::from_u32(pid);
+
+        if !sys.refresh_process(pid) {
+            self.processes.retain(|p| p.pid != pid.as_u32());
+            return false;
+        }
+
+        let cpu_count = sys.cpus().len();
+
+        if let Some(process) = sys.process(pid) {
+            let mut data = process_data(pid, process, cpu_count);
+
+            match self.processes.iter_mut().find(|p| p.pid == data.pid) {
+                // the rate is kept until the next full refresh measures it again
+                Some(existing) => {
+                    data.major_fault_rate = existing.major_fault_rate;
+                    *existing = data
+                }
+                None => self.processes.push(data),
+            }
+        }
+
+        drop(sys);
+        self.compute_children();
+
+        true
+    }
+
+    // one pass over the processes, filling in every parent's children_pids
+    pub fn compute_children(&mut self) {
+        let index_of = self
+            .processes
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.pid, i))
+            .collect::<HashMap<u32, usize>>();
+
+        for process in &mut self.processes {
+            process.children_pids.clear();
+        }
+
+        for i in 0..self.processes.len() {
+            let pid = self.processes[i].pid;
+            let parent = self.processes[i]
+                .parent_pid
+                .and_then(|p| index_of.get(&p).copied());
+
+            if let Some(parent) = parent {
+                self.processes[parent].children_pids.push(pid);
+            }
+        }
+    }
+
+    pub fn memory_pressure_level(&self) -> MemoryPressure {
+        MemoryPressure::from_percent(self.usage.memory_percent())
+    }
+
+    pub fn is_oom_risk(&self) -> bool {
+        // with no swap at all there is nothing left to fall back on either
+        let swap_full = self.usage.total_swap == 0
+            || self.usage.swap as f32 / self.usage.total_swap as f32 > 0.5;
+
+        self.memory_pressure_level() == MemoryPressure::Critical && swap_full
+    }
+
+    // processes younger than each of `boundaries`, which go from shortest to longest,
+    // and one more count for everything older
+    pub fn process_age_histogram(&self, boundaries: &[Duration]) -> Vec<u64> {
+        let mut counts = vec![0; boundaries.len() + 1];
+
+        for process in self.processes.iter().filter(|p| p.thread_of.is_none()) {
+            let age = process.total_time.max(0.0) as f64;
+            let bucket = boundaries
+                .iter()
+                .position(|b| age < b.as_secs_f64())
+                .unwrap_or(boundaries.len());
+
+            counts[bucket] += 1;
+        }
+
+        counts
+    }
+
+    // how much of the memory in use the processes account for. shared pages are counted
+    // once per process, so this can go past 100
+    pub fn memory_attribution_percent(&self) -> f32 {
+        if self.usage.memory == 0 {
+            return 0.0;
+        }
+
+        SystemData::memory_used_by_processes(&self.processes) as f32 / self.usage.memory as f32
+            * 100.0
+    }
+
+    pub fn health_components(&self) -> HealthComponents {
+        let usage = &self.usage;
+        let percent = |part: u64, whole: u64| {
+            if whole == 0 {
+                0.0
+            } else {
+                (part as f32 / whole as f32 * 100.0).min(100.0)
+            }
+        };
+
+        let packets = usage
+            .networks
+            .iter()
+            .map(|n| n.total_packets_sent + n.total_packets_recv)
+            .sum();
+
+        HealthComponents {
+            cpu: usage.average_cpu_usage().clamp(0.0, 100.0),
+            memory: usage.memory_percent(),
+            swap: percent(usage.swap, usage.total_swap),
+            disk: usage.disks.iter().map(|d| d.percent).fold(0.0, f32::max),
+            network_errors: percent(usage.total_network_errors(), packets),
+        }
+    }
+
+    // one figure for the whole machine from 0 to 100, higher is healthier, see
+    // HealthComponents::score for how it's weighted
+    pub fn health_score(&self) -> f32 {
+        self.health_components().score()
+    }
+
+    // the cpu every process is using between them, threads are already in their process's
+    // figure. the denominator of the process table's relative cpu
+    pub fn busy_cpu(&self) -> f32 {
+        self.processes
+            .iter()
+            .filter(|p| p.thread_of.is_none())
+            .map(|p| p.cpu_usage)
+            .sum()
+    }
+
+    pub fn process_count(&self) -> usize {
+        self.processes.iter().filter(|p| p.thread_of.is_none()).count()
+    }
+
+    // summed over the processes whose count is known, None where none of them are
+    pub fn total_threads(&self) -> Option<u64> {
+        self.processes
+            .iter()
+            .filter(|p| p.thread_of.is_none())
+            .filter_map(|p| p.threads)
+            .reduce(|a, b| a + b)
+    }
+
+    // like the age histogram these leave out thread entries, so they agree with ps
+    pub fn processes_by_state(&self, state: ProcessState) -> Vec<&ProcessData> {
+        self.processes
+            .iter()
+            .filter(|p| p.thread_of.is_none() && p.process_state() == state)
+            .collect()
+    }
+
+    pub fn running_processes(&self) -> Vec<&ProcessData> {
+        self.processes_by_state(ProcessState::Running)
+    }
+
+    pub fn sleeping_processes(&self) -> Vec<&ProcessData> {
+        self.processes_by_state(ProcessState::Sleeping)
+    }
+
+    pub fn zombie_processes(&self) -> Vec<&ProcessData> {
+        self.processes_by_state(ProcessState::Zombie)
+    }
+
+    pub fn stopped_processes(&self) -> Vec<&ProcessData> {
+        self.processes_by_state(ProcessState::Stopped)
+    }
+
+    pub fn process_state_counts(&self) -> HashMap<ProcessState, usize> {
+        let mut counts = HashMap::new();
+
+        for process in self.processes.iter().filter(|p| p.thread_of.is_none()) {
+            *counts.entry(process.process_state()).or_insert(0) += 1;
+        }
+
+        counts
+    }
+
+    // busiest owner first, threads are left out as their process already counts them
+    pub fn user_breakdown(&self) -> Vec<UserUsage> {
+        let mut by_uid: HashMap<Option<&str>, UserUsage> = HashMap::new();
+
+        for process in self.processes.iter().filter(|p| p.thread_of.is_none()) {
+            let usage = by_uid.entry(process.uid.as_deref()).or_insert_with(|| UserUsage {
+                name: self.user_name(process.uid.as_deref()),
+                uid: process.uid.clone(),
+                cpu_usage: 0.0,
+                ram: 0,
+                process_count: 0,
+            });
+
+            usage.cpu_usage += process.cpu_usage;
+            usage.ram += process.ram;
+            usage.process_count += 1;
+        }
+
+        let mut breakdown = by_uid.into_values().collect::<Vec<UserUsage>>();
+        breakdown.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage).then(b.ram.cmp(&a.ram)));
+
+        breakdown
+    }
+
+    pub fn user(&self, uid: Option<&str>) -> Option<&User> {
+        self.spec.users.iter().find(|u| Some(u.uid.as_str()) == uid)
+    }
+
+    fn user_name(&self, uid: Option<&str>) -> String {
+        let Some(uid) = uid else {
+            return "unknown".to_string();
+        };
+
+        self.user(Some(uid)).map_or(uid.to_string(), |u| u.name.clone())
+    }
+
+    // the owner of pidwatch itself
+    pub fn current_uid(&self) -> Option<&str> {
+        self.processes
+            .iter()
+            .find(|p| p.pid == std::process::id())
+            .and_then(|p| p.uid.as_deref())
+    }
+
+    pub fn compare_with_baseline(&self, baseline: &SystemInfo) -> BaselineReport {
+        let disk_used =
+            |sys: &SystemInfo| sys.usage.disks.iter().map(|d| d.used as i64).sum::<i64>();
+
+        // only interfaces that were there both times, counters restart when one comes back
+        let mut network_sent = 0;
+        let mut network_recv = 0;
+
+        for network in &self.usage.networks {
+            if let Some(old) = baseline.usage.networks.iter().find(|n| n.name == network.name) {
+                network_sent += network.total_sent.saturating_sub(old.total_sent);
+                network_recv += network.total_recv.saturating_sub(old.total_recv);
+            }
+        }
+
+        // threads share their process's memory, they'd only be counted twice
+        fn processes(sys: &SystemInfo) -> Vec<&ProcessData> {
+            sys.processes.iter().filter(|p| p.thread_of.is_none()).collect()
+        }
+
+        let (current, old) = (processes(self), processes(baseline));
+
+        let baseline_by_pid = old.iter().map(|p| (p.pid, *p)).collect::<HashMap<u32, _>>();
+        let current_pids = current.iter().map(|p| p.pid).collect::<HashSet<u32>>();
+
+        let mut memory_growth = current
+            .iter()
+            .filter_map(|p| {
+                let old = baseline_by_pid.get(&p.pid)?;
+                Some((p.pid, p.name.clone(), p.ram as i64 - old.ram as i64))
+            })
+            .filter(|(_, _, growth)| *growth > 0)
+            .collect::<Vec<_>>();
+
+        memory_growth.sort_by_key(|(_, _, growth)| std::cmp::Reverse(*growth));
+        memory_growth.truncate(BASELINE_GROWTH_SHOWN);
+
+        BaselineReport {
+            cpu_delta: self.usage.average_cpu_usage() - baseline.usage.average_cpu_usage(),
+            memory_delta: self.usage.memory as i64 - baseline.usage.memory as i64,
+            swap_delta: self.usage.swap as i64 - baseline.usage.swap as i64,
+            disk_used_delta: disk_used(self) - disk_used(baseline),
+            network_sent,
+            network_recv,
+            process_count_delta: current.len() as i64 - old.len() as i64,
+            new_processes: current
+                .iter()
+                .filter(|p| !baseline_by_pid.contains_key(&p.pid))
+                .map(|p| (p.pid, p.name.clone()))
+                .collect(),
+            exited_processes: old
+                .iter()
+                .filter(|p| !current_pids.contains(&p.pid))
+                .map(|p| (p.pid, p.name.clone()))
+                .collect(),
+            memory_growth,
+        }
+    }
+}
+
+// how many of the processes that grew the most a BaselineReport keeps
+const BASELINE_GROWTH_SHOWN: usize = 5;
+
+// what changed since a snapshot was taken, see SystemInfo::compare_with_baseline
+#[derive(Debug, Clone)]
+pub struct BaselineReport {
+    // percentage points of average cpu usage
+    pub cpu_delta: f32,
+    // bytes
+    pub memory_delta: i64,
+    pub swap_delta: i64,
+    pub disk_used_delta: i64,
+    // bytes moved since, over the interfaces present both times
+    pub network_sent: u64,
+    pub network_recv: u64,
+    pub process_count_delta: i64,
+    // (pid, name)
+    pub new_processes: Vec<(u32, String)>,
+    pub exited_processes: Vec<(u32, String)>,
+    // (pid, name, bytes grown), biggest first
+    pub memory_growth: Vec<(u32, String, i64)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_pressure_thresholds() {
+        assert_eq!(MemoryPressure::from_percent(59.9), MemoryPressure::Normal);
+        assert_eq!(MemoryPressure::from_percent(60.0), MemoryPressure::Elevated);
+        assert_eq!(MemoryPressure::from_percent(79.9), MemoryPressure::Elevated);
+        assert_eq!(MemoryPressure::from_percent(80.0), MemoryPressure::High);
+        assert_eq!(MemoryPressure::from_percent(90.0), MemoryPressure::High);
+        assert_eq!(MemoryPressure::from_percent(90.1), MemoryPressure::Critical);
+    }
+
+    fn with_memory(memory: u64, swap: u64, total_swap: u64) -> SystemInfo {
+        let mut sys = SystemInfo::new();
+        sys.usage.total_memory = 100;
+        sys.usage.memory = memory;
+        sys.usage.swap = swap;
+        sys.usage.total_swap = total_swap;
+
+        sys
+    }
+
+    #[test]
+    fn oom_risk_needs_critical_memory_and_swap_past_half() {
+        assert!(with_memory(95, 60, 100).is_oom_risk());
+        assert!(!with_memory(95, 40, 100).is_oom_risk());
+        assert!(!with_memory(85, 60, 100).is_oom_risk());
+    }
+
+    #[test]
+    fn oom_risk_treats_no_swap_as_full() {
+        assert!(with_memory(95, 0, 0).is_oom_risk());
+        assert!(!with_memory(85, 0, 0).is_oom_risk());
+    }
+
+    fn network(name: &str) -> Network {
+        Network {
+            name: name.to_string(),
+            mac: String::new(),
+            total_sent: 0,
+            total_recv: 0,
+            total_packets_sent: 0,
+            total_packets_recv: 0,
+            total_errors_sent: 0,
+            total_errors_recv: 0,
+            total_drops_sent: 0,
+            total_drops_recv: 0,
+            sent_per_sec: 0,
+            recv_per_sec: 0,
+            ipv4_addrs: vec![],
+            ipv6_addrs: vec![],
+            kind: InterfaceKind::from_name(name),
+        }
+    }
+
+    #[test]
+    fn networks_appearing_after_startup_join_the_order() {
+        let mut sys = SystemInfo::new();
+        let mut order = vec![];
+
+        sys.usage.extend_network_order(&mut order);
+        assert!(order.is_empty());
+
+        sys.usage.networks = vec![network("wlan0"), network("lo")];
+        sys.usage.extend_network_order(&mut order);
+        assert_eq!(order, ["wlan0", "lo"]);
+
+        // known ones keep their place, whatever order they're listed in now
+        sys.usage.networks = vec![network("lo"), network("tun0"), network("wlan0")];
+        sys.usage.extend_network_order(&mut order);
+        assert_eq!(order, ["wlan0", "lo", "tun0"]);
+    }
+
+    fn child_of(pid: u32, name: &str, parent: u32) -> ProcessData {
+        ProcessData {
+            parent_pid: Some(parent),
+            ..ProcessData::named(pid, name)
+        }
+    }
+
+    #[test]
+    fn compute_children_fills_in_each_parent() {
+        let mut sys = SystemInfo::new();
+        sys.processes = vec![
+            child_of(20, "bash", 10),
+            ProcessData::named(1, "init"),
+            child_of(10, "sshd", 1),
+            child_of(21, "vim", 10),
+            // its parent isn't listed, it's left out rather than attached anywhere
+            child_of(30, "orphan", 999),
+        ];
+        // stale children are cleared first
+        sys.processes[4].children_pids = vec![42];
+
+        sys.compute_children();
+
+        let children = |pid: u32| {
+            let process = sys.processes.iter().find(|p| p.pid == pid).unwrap();
+            process.children_pids.clone()
+        };
+
+        assert_eq!(children(1), [10]);
+        assert_eq!(children(10), [20, 21]);
+        assert!(children(20).is_empty());
+        assert!(children(30).is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parse_passwd_reads_home_and_shell() {
+        let passwd = "\
+# a comment:x:0:0::/root:/bin/sh
+root:x:0:0:root:/root:/bin/bash
+alice:x:1000:1000:Alice:/home/alice:/usr/bin/zsh
+short:x:1001
+nologin:x:1002:1002::/nonexistent:
+";
+        let users = parse_passwd(passwd);
+
+        assert_eq!(users.len(), 3);
+        assert_eq!(users["0"], (PathBuf::from("/root"), "/bin/bash".to_string()));
+        assert_eq!(users["1000"], (PathBuf::from("/home/alice"), "/usr/bin/zsh".to_string()));
+        assert_eq!(users["1002"], (PathBuf::from("/nonexistent"), String::new()));
+        assert!(!users.contains_key("1001"));
+    }
+
+    #[test]
+    fn an_empty_shell_has_no_name() {
+        let user = |shell: &str| User {
+            name: "alice".to_string(),
+            uid: "1000".to_string(),
+            groups: vec![],
+            home_dir: None,
+            shell: Some(shell.to_string()),
+        };
+
+        assert_eq!(user("/usr/bin/zsh").shell_name(), Some("zsh"));
+        assert_eq!(user("").shell_name(), None);
+    }
+
+    #[test]
+    fn is_64bit_by_architecture() {
+        let is_64bit = |architecture: &str| {
+            let mut sys = SystemInfo::new();
+            sys.spec.architecture = architecture.to_string();
+            sys.spec.is_64bit()
+        };
+
+        assert!(is_64bit("x86_64"));
+        assert!(is_64bit("aarch64"));
+        assert!(is_64bit("s390x"));
+        assert!(!is_64bit("i686"));
+        assert!(!is_64bit("armv7l"));
+    }
+
+    #[test]
+    fn primary_ipv4_skips_loopback() {
+        let with_addresses = |addresses: &[&str]| Network {
+            ipv4_addrs: addresses.iter().map(|a| a.parse().unwrap()).collect(),
+            ..network("eth0")
+        };
+
+        assert_eq!(with_addresses(&[]).primary_ipv4(), None);
+        assert_eq!(with_addresses(&["127.0.0.1"]).primary_ipv4(), None);
+        assert_eq!(
+            with_addresses(&["127.0.0.1", "192.168.1.20", "10.0.0.5"]).primary_ipv4(),
+            Some(Ipv4Addr::new(192, 168, 1, 20))
+        );
+    }
+
+    #[test]
+    fn address_scopes() {
+        let scope = |address: &str| AddressScope::of(address.parse().unwrap());
+
+        assert_eq!(scope("127.0.0.1"), AddressScope::Loopback);
+        assert_eq!(scope("169.254.10.1"), AddressScope::LinkLocal);
+        assert_eq!(scope("10.1.2.3"), AddressScope::Private);
+        assert_eq!(scope("172.16.0.1"), AddressScope::Private);
+        assert_eq!(scope("192.168.0.1"), AddressScope::Private);
+        assert_eq!(scope("8.8.8.8"), AddressScope::Public);
+        assert_eq!(scope("::1"), AddressScope::Loopback);
+        assert_eq!(scope("fe80::1"), AddressScope::LinkLocal);
+        assert_eq!(scope("fd12:3456::1"), AddressScope::Private);
+        assert_eq!(scope("2001:4860:4860::8888"), AddressScope::Public);
+    }
+
+    fn in_state(pid: u32, state: &str) -> ProcessData {
+        ProcessData {
+            state: state.to_string(),
+            ..ProcessData::named(pid, "worker")
+        }
+    }
+
+    #[test]
+    fn processes_are_counted_by_state_without_threads() {
+        let mut sys = SystemInfo::new();
+        sys.processes = vec![
+            in_state(1, "Runnable"),
+            in_state(2, "Sleeping"),
+            in_state(3, "Sleeping"),
+            in_state(4, "Zombie"),
+            in_state(5, "Stopped"),
+            // a thread of 1, its process already counts
+            ProcessData {
+                thread_of: Some(1),
+                ..in_state(6, "Runnable")
+            },
+        ];
+
+        let pids = |state: ProcessState| {
+            sys.processes_by_state(state).iter().map(|p| p.pid).collect::<Vec<u32>>()
+        };
+
+        assert_eq!(pids(ProcessState::Running), [1]);
+        assert_eq!(pids(ProcessState::Sleeping), [2, 3]);
+        assert_eq!(sys.zombie_processes().len(), 1);
+        assert_eq!(sys.stopped_processes().len(), 1);
+        assert!(pids(ProcessState::DiskSleep).is_empty());
+
+        let counts = sys.process_state_counts();
+        assert_eq!(counts[&ProcessState::Running], 1);
+        assert_eq!(counts[&ProcessState::Sleeping], 2);
+        assert_eq!(counts[&ProcessState::Zombie], 1);
+        assert_eq!(counts[&ProcessState::Stopped], 1);
+        assert_eq!(counts.get(&ProcessState::DiskSleep), None);
+        assert_eq!(counts.values().sum::<usize>(), 5);
+    }
+
+    #[test]
+    fn process_states_from_every_platforms_status() {
+        let state = ProcessState::from_status;
+
+        // every platform
+        assert_eq!(state("Runnable"), ProcessState::Running);
+        assert_eq!(state("Sleeping"), ProcessState::Sleeping);
+        assert_eq!(state("Stopped"), ProcessState::Stopped);
+        assert_eq!(state("Zombie"), ProcessState::Zombie);
+        assert_eq!(state("Idle"), ProcessState::Idle);
+        // linux
+        assert_eq!(state("UninterruptibleDiskSleep"), ProcessState::DiskSleep);
+        assert_eq!(state("Tracing"), ProcessState::Stopped);
+        assert_eq!(state("Dead"), ProcessState::Zombie);
+        assert_eq!(state("Waking"), ProcessState::Unknown);
+        // freebsd
+        assert_eq!(state("LockBlocked"), ProcessState::Sleeping);
+        // windows only knows the one, everything else is unknown
+        assert_eq!(state("Unknown"), ProcessState::Unknown);
+        assert_eq!(state(""), ProcessState::Unknown);
+    }
+
+    #[test]
+    fn available_memory_is_low_below_a_tenth() {
+        let with_available = |available_memory: u64| {
+            let mut sys = SystemInfo::new();
+            sys.usage.total_memory = 1000;
+            sys.usage.available_memory = available_memory;
+            sys.usage.is_available_memory_low()
+        };
+
+        assert!(with_available(0));
+        assert!(with_available(99));
+        assert!(!with_available(100));
+        assert!(!with_available(800));
+    }
+
+    #[test]
+    fn per_core_summary_leaves_out_unknown_speeds_and_vendors() {
+        let core = |clock_speed: f32, vendor: &str| Cpu {
+            name: String::new(),
+            usage: 12.5,
+            clock_speed,
+            vendor: vendor.to_string(),
+            brand: "Apple M2 Pro".to_string(),
+            temperature: None,
+            steal: None,
+        };
+
+        let mut sys = SystemInfo::new();
+        sys.usage.cpus = vec![core(0.0, ""), core(3200.0, "GenuineIntel"), core(0.0, "  ")];
+
+        let summary = sys.usage.per_core_summary();
+
+        assert_eq!(summary.len(), 3);
+        assert_eq!(summary[0].core_id, 0);
+        assert_eq!(summary[0].clock_speed, None);
+        assert_eq!(summary[0].vendor, None);
+        assert_eq!(summary[0].steal, 0.0);
+        assert_eq!(summary[1].clock_speed, Some(3200.0));
+        assert_eq!(summary[1].vendor.as_deref(), Some("GenuineIntel"));
+        assert_eq!(summary[2].core_id, 2);
+        assert_eq!(summary[2].vendor, None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn deleted_exe_links() {
+        assert!(is_deleted_exe(Path::new("/usr/bin/nginx (deleted)")));
+        assert!(!is_deleted_exe(Path::new("/usr/bin/nginx")));
+        // a name that only contains it is still there
+        assert!(!is_deleted_exe(Path::new("/opt/app (deleted)/bin/app")));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn a_running_exe_is_not_deleted() {
+        assert!(!read_exe_deleted(std::process::id()));
+    }
+
+    #[test]
+    fn a_reboot_is_required_once_a_marker_exists() {
+        let dir = std::env::temp_dir().join(format!("pidwatch-reboot-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let markers = [dir.join("reboot-required"), dir.join("reboot-required.pkgs")];
+
+        let before = reboot_required(&markers);
+        std::fs::write(&markers[1], "linux-image-6.8.0\n").unwrap();
+        let after = reboot_required(&markers);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!before);
+        assert!(after);
+    }
+
+    #[test]
+    fn busy_cpu_sums_processes_without_their_threads() {
+        let using = |pid: u32, cpu_usage: f32| ProcessData {
+            cpu_usage,
+            ..ProcessData::named(pid, "worker")
+        };
+
+        let mut sys = SystemInfo::new();
+        sys.processes = vec![
+            using(1, 30.0),
+            using(2, 10.0),
+            ProcessData {
+                thread_of: Some(1),
+                ..using(3, 20.0)
+            },
+        ];
+
+        assert_eq!(sys.busy_cpu(), 40.0);
+    }
+}
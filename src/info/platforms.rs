@@ -0,0 +1,141 @@
+// what pidwatch can read where. most of what's only known on linux comes from /proc and
+// /sys, which sysinfo doesn't cover, so elsewhere those fields are None, false or empty
+// rather than wrong
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Linux,
+    MacOs,
+    Windows,
+    FreeBsd,
+    Other,
+}
+
+// the optional parts, everything sysinfo reads everywhere (usage, memory, disks, networks,
+// the process list) isn't listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    // Cpu::temperature, from whatever sensors sysinfo finds. on windows they usually need
+    // pidwatch to run as administrator
+    Temperature,
+    // SystemData::cpu_steal and Cpu::steal, from /proc/stat
+    CpuSteal,
+    // ProcessData::cpu_steal_ms, from /proc/<pid>/schedstat
+    SchedulingDelay,
+    // ProcessData::sched_policy
+    SchedPolicy,
+    // ProcessData::thread_of and ProcessData::threads, without them threads can't be merged
+    Threads,
+    // ProcessData::major_faults
+    MajorFaults,
+    // ProcessData::exe_deleted, from where /proc/<pid>/exe points
+    DeletedExecutables,
+    // the detail view's memory, io, limits and environment, see procfs::ProcessExtras
+    ProcessExtras,
+    // the detail view's syscalls tab
+    Syscalls,
+    // reading and setting ionice classes, see procfs::IoPriority
+    IoPriority,
+    // sockets per process, see the connections module
+    Connections,
+    // Disk::read_per_sec and friends, from /proc/diskstats
+    DiskIo,
+    // Disk::inode_used and Disk::inode_total, from statvfs
+    Inodes,
+    // Network::total_drops_sent and total_drops_recv, and an interface kind from the link
+    // type rather than guessed from the name, from /sys/class/net
+    InterfaceDetails,
+    // the addresses on each interface
+    InterfaceAddresses,
+    // SystemSpec::pending_reboot, from the markers package managers leave behind
+    PendingReboot,
+    // SystemSpec::environment, the container or init system pidwatch runs under
+    Environment,
+    // --user and --drop-privileges
+    SwitchUser,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 18] = [
+        Feature::Temperature,
+        Feature::CpuSteal,
+        Feature::SchedulingDelay,
+        Feature::SchedPolicy,
+        Feature::Threads,
+        Feature::MajorFaults,
+        Feature::DeletedExecutables,
+        Feature::ProcessExtras,
+        Feature::Syscalls,
+        Feature::IoPriority,
+        Feature::Connections,
+        Feature::DiskIo,
+        Feature::Inodes,
+        Feature::InterfaceDetails,
+        Feature::InterfaceAddresses,
+        Feature::PendingReboot,
+        Feature::Environment,
+        Feature::SwitchUser,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Feature::Temperature => "cpu temperature",
+            Feature::CpuSteal => "cpu steal",
+            Feature::SchedulingDelay => "per-process scheduling delay",
+            Feature::SchedPolicy => "scheduling policy",
+            Feature::Threads => "thread counts and merging",
+            Feature::MajorFaults => "major page faults",
+            Feature::DeletedExecutables => "deleted executables",
+            Feature::ProcessExtras => "process memory, io, limits and environment",
+            Feature::Syscalls => "current syscall",
+            Feature::IoPriority => "io priority",
+            Feature::Connections => "process sockets",
+            Feature::DiskIo => "disk io rates",
+            Feature::Inodes => "inode usage",
+            Feature::InterfaceDetails => "interface kinds and dropped packets",
+            Feature::InterfaceAddresses => "interface addresses",
+            Feature::PendingReboot => "pending reboot",
+            Feature::Environment => "container and init system",
+            Feature::SwitchUser => "switching user",
+        }
+    }
+}
+
+// statvfs and setuid are all the other unixes get beyond sysinfo
+const UNIX_FEATURES: [Feature; 3] = [Feature::Temperature, Feature::Inodes, Feature::SwitchUser];
+
+impl Platform {
+    pub fn label(self) -> &'static str {
+        match self {
+            Platform::Linux => "linux",
+            Platform::MacOs => "macos",
+            Platform::Windows => "windows",
+            Platform::FreeBsd => "freebsd",
+            Platform::Other => "other",
+        }
+    }
+
+    pub fn supported_features(self) -> &'static [Feature] {
+        match self {
+            Platform::Linux => &Feature::ALL,
+            Platform::MacOs | Platform::FreeBsd => &UNIX_FEATURES,
+            Platform::Windows => &[Feature::Temperature],
+            Platform::Other => &[],
+        }
+    }
+}
+
+// the one pidwatch was built for
+pub fn current_platform() -> Platform {
+    if cfg!(target_os = "linux") {
+        Platform::Linux
+    } else if cfg!(target_os = "macos") {
+        Platform::MacOs
+    } else if cfg!(windows) {
+        Platform::Windows
+    } else if cfg!(target_os = "freebsd") {
+        Platform::FreeBsd
+    } else {
+        Platform::Other
+    }
+}
@@ -0,0 +1,285 @@
+use std::fmt::Display;
+
+use crate::util;
+
+// how the panels word their figures, compact shortens the labels and drops digits so more
+// fits on a small screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelMode {
+    Full,
+    Compact,
+}
+
+// the unit sizes are shown in, cycled with u. auto picks one to suit each figure, the others
+// pin every memory, swap, disk and network figure to the same one so they line up across
+// panels. KB, MB and GB are powers of 1000, GiB of 1024
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeUnit {
+    Auto,
+    Kb,
+    Mb,
+    Gb,
+    Gib,
+}
+
+impl SizeUnit {
+    pub const ALL: [SizeUnit; 5] = [
+        SizeUnit::Auto,
+        SizeUnit::Kb,
+        SizeUnit::Mb,
+        SizeUnit::Gb,
+        SizeUnit::Gib,
+    ];
+
+    pub fn next(self) -> SizeUnit {
+        let index = SizeUnit::ALL.iter().position(|u| *u == self).unwrap_or_default();
+        SizeUnit::ALL[(index + 1) % SizeUnit::ALL.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SizeUnit::Auto => "auto",
+            SizeUnit::Kb => "KB",
+            SizeUnit::Mb => "MB",
+            SizeUnit::Gb => "GB",
+            SizeUnit::Gib => "GiB",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<SizeUnit, String> {
+        SizeUnit::ALL
+            .into_iter()
+            .find(|u| u.label() == value)
+            .ok_or_else(|| format!("expected auto, KB, MB, GB or GiB, got '{}'", value))
+    }
+
+    // bytes in one of it, None for auto
+    fn scale(self) -> Option<f64> {
+        match self {
+            SizeUnit::Auto => None,
+            SizeUnit::Kb => Some(1e3),
+            SizeUnit::Mb => Some(1e6),
+            SizeUnit::Gb => Some(1e9),
+            SizeUnit::Gib => Some(1024.0 * 1024.0 * 1024.0),
+        }
+    }
+}
+
+// the labels shared between panels, each with a long and a short form
+#[derive(Debug, Clone, Copy)]
+pub enum Label {
+    AverageUsage,
+    ClockSpeed,
+    Steal,
+    Hostname,
+    Os,
+    Kernel,
+    Arch,
+    Uptime,
+    Env,
+    Users,
+    Processes,
+    Threads,
+    Unattributed,
+    Used,
+    Available,
+    Total,
+    Sent,
+    Received,
+    Peak,
+}
+
+// what the cpu panel shows of the cores' clock speeds, from the clock_speed setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockStat {
+    Avg,
+    Max,
+    Min,
+}
+
+impl ClockStat {
+    pub const ALL: [ClockStat; 3] = [ClockStat::Avg, ClockStat::Max, ClockStat::Min];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ClockStat::Avg => "avg",
+            ClockStat::Max => "max",
+            ClockStat::Min => "min",
+        }
+    }
+
+    // a comma separated list, e.g. "avg, max", shown in that order
+    pub fn parse_list(value: &str) -> Result<Vec<ClockStat>, String> {
+        let mut stats = vec![];
+
+        for name in value.split(',').map(str::trim) {
+            let stat = ClockStat::ALL
+                .into_iter()
+                .find(|s| s.label() == name)
+                .ok_or_else(|| format!("expected avg, max or min, got '{}'", name))?;
+
+            if !stats.contains(&stat) {
+                stats.push(stat);
+            }
+        }
+
+        Ok(stats)
+    }
+
+    // over every core's clock speed, None without any
+    pub fn of(self, mhz: &[f32]) -> Option<f32> {
+        if mhz.is_empty() {
+            return None;
+        }
+
+        Some(match self {
+            ClockStat::Avg => mhz.iter().sum::<f32>() / mhz.len() as f32,
+            ClockStat::Max => mhz.iter().copied().fold(f32::MIN, f32::max),
+            ClockStat::Min => mhz.iter().copied().fold(f32::MAX, f32::min),
+        })
+    }
+}
+
+impl LabelMode {
+    pub fn toggle(self) -> LabelMode {
+        match self {
+            LabelMode::Full => LabelMode::Compact,
+            LabelMode::Compact => LabelMode::Full,
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<LabelMode, String> {
+        match value {
+            "full" => Ok(LabelMode::Full),
+            "compact" => Ok(LabelMode::Compact),
+            _ => Err(format!("expected full or compact, got '{}'", value)),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LabelMode::Full => "full",
+            LabelMode::Compact => "compact",
+        }
+    }
+
+    pub fn text(self, label: Label) -> &'static str {
+        let (full, compact) = match label {
+            Label::AverageUsage => ("Average Usage", "Avg"),
+            Label::ClockSpeed => ("Clock Speed", "Clk"),
+            Label::Steal => ("Steal", "Stl"),
+            Label::Hostname => ("Hostname", "Host"),
+            Label::Os => ("OS", "OS"),
+            Label::Kernel => ("Kernel", "Kern"),
+            Label::Arch => ("Arch", "Arch"),
+            Label::Uptime => ("Uptime", "Up"),
+            Label::Env => ("Env", "Env"),
+            Label::Users => ("Users", "Usr"),
+            Label::Processes => ("Processes", "Procs"),
+            Label::Threads => ("Threads", "Thr"),
+            Label::Unattributed => ("Unattributed", "Unattr"),
+            Label::Used => ("Used", "Used"),
+            Label::Available => ("Available", "Avail"),
+            Label::Total => ("Total", "Tot"),
+            Label::Sent => ("sent", "↑"),
+            Label::Received => ("received", "↓"),
+            Label::Peak => ("peak", "pk"),
+        };
+
+        if self == LabelMode::Full {
+            full
+        } else {
+            compact
+        }
+    }
+
+    // "Hostname: box" or "Host box"
+    pub fn field(self, label: Label, value: impl Display) -> String {
+        if self == LabelMode::Full {
+            format!("{}: {}", self.text(label), value)
+        } else {
+            format!("{} {}", self.text(label), value)
+        }
+    }
+
+    pub fn percent(self, percent: f32) -> String {
+        if self == LabelMode::Full {
+            format!("{:.2}%", percent)
+        } else {
+            format!("{:.1}%", percent)
+        }
+    }
+
+    // sysinfo reports clock speeds in MHz
+    pub fn frequency(self, mhz: f32) -> String {
+        if self == LabelMode::Full {
+            format!("{:.2} GHz", mhz / 1000.0)
+        } else {
+            format!("{:.1}G", mhz / 1000.0)
+        }
+    }
+
+    // "avg 3.40 / max 4.80 / min 2.10 GHz" or "avg 3.4 max 4.8 min 2.1G"
+    pub fn frequencies(self, stats: &[(ClockStat, f32)]) -> String {
+        if stats.is_empty() {
+            return "unknown".to_string();
+        }
+
+        let (separator, precision, unit) = if self == LabelMode::Full {
+            (" / ", 2, " GHz")
+        } else {
+            (" ", 1, "G")
+        };
+
+        let figures = stats
+            .iter()
+            .map(|(stat, mhz)| format!("{} {:.*}", stat.label(), precision, mhz / 1000.0))
+            .collect::<Vec<String>>()
+            .join(separator);
+
+        format!("{}{}", figures, unit)
+    }
+
+    // "1.23 GB" or "1.2G" in a pinned unit, None for auto
+    fn in_unit(self, unit: SizeUnit, bytes: u64) -> Option<String> {
+        let value = bytes as f64 / unit.scale()?;
+
+        Some(if self == LabelMode::Full {
+            format!("{:.2} {}", value, unit.label())
+        } else {
+            format!("{:.1}{}", value, unit.label().trim_end_matches('B'))
+        })
+    }
+
+    pub fn gigabytes(self, unit: SizeUnit, bytes: u64) -> String {
+        if let Some(pinned) = self.in_unit(unit, bytes) {
+            return pinned;
+        }
+
+        let gigabytes = bytes as f32 / 1024.0 / 1024.0 / 1024.0;
+
+        if self == LabelMode::Full {
+            format!("{:.2} GB", gigabytes)
+        } else {
+            format!("{:.1}G", gigabytes)
+        }
+    }
+
+    pub fn bytes(self, unit: SizeUnit, bytes: u64) -> String {
+        if let Some(pinned) = self.in_unit(unit, bytes) {
+            pinned
+        } else if self == LabelMode::Full {
+            util::format_bytes(bytes)
+        } else {
+            util::format_bytes_compact(bytes)
+        }
+    }
+
+    pub fn duration(self, secs: u64) -> String {
+        if self == LabelMode::Full {
+            util::format_duration(secs)
+        } else {
+            util::format_duration_short(secs)
+        }
+    }
+}
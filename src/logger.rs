@@ -0,0 +1,206 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{export, info::SystemInfo, json::Value, util};
+
+// the parts of a refresh --log writes, --log-fields picks some of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogField {
+    Cpu,
+    Memory,
+    Disks,
+    Networks,
+    Processes,
+}
+
+impl LogField {
+    pub const ALL: [LogField; 5] = [
+        LogField::Cpu,
+        LogField::Memory,
+        LogField::Disks,
+        LogField::Networks,
+        LogField::Processes,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LogField::Cpu => "cpu",
+            LogField::Memory => "memory",
+            LogField::Disks => "disks",
+            LogField::Networks => "networks",
+            LogField::Processes => "processes",
+        }
+    }
+
+    // a comma separated list, e.g. "cpu,memory"
+    pub fn parse_list(value: &str) -> Result<Vec<LogField>, String> {
+        let mut fields = vec![];
+
+        for name in value.split(',').map(str::trim) {
+            let field = LogField::ALL
+                .into_iter()
+                .find(|f| f.label() == name)
+                .ok_or_else(|| {
+                    format!(
+                        "unknown field '{}', expected cpu, memory, disks, networks or processes",
+                        name
+                    )
+                })?;
+
+            if !fields.contains(&field) {
+                fields.push(field);
+            }
+        }
+
+        Ok(fields)
+    }
+
+    fn value(self, sys: &SystemInfo) -> Value {
+        let usage = &sys.usage;
+
+        match self {
+            LogField::Cpu => Value::object([
+                ("average", usage.average_cpu_usage().into()),
+                (
+                    "cores",
+                    Value::Array(usage.cpus.iter().map(|c| c.usage.into()).collect()),
+                ),
+            ]),
+            LogField::Memory => Value::object([
+                ("used", usage.memory.into()),
+                ("available", usage.available_memory.into()),
+                ("total", usage.total_memory.into()),
+                ("swap", usage.swap.into()),
+                ("total_swap", usage.total_swap.into()),
+            ]),
+            LogField::Disks => Value::Array(
+                usage
+                    .disks
+                    .iter()
+                    .map(|d| {
+                        Value::object([
+                            ("mount", d.mount.as_str().into()),
+                            ("used", d.used.into()),
+                            ("total", d.total.into()),
+                            ("read_per_sec", d.read_per_sec.into()),
+                            ("write_per_sec", d.write_per_sec.into()),
+                        ])
+                    })
+                    .collect(),
+            ),
+            LogField::Networks => Value::Array(
+                usage
+                    .networks
+                    .iter()
+                    .map(|n| {
+                        Value::object([
+                            ("name", n.name.as_str().into()),
+                            ("sent_per_sec", n.sent_per_sec.into()),
+                            ("recv_per_sec", n.recv_per_sec.into()),
+                        ])
+                    })
+                    .collect(),
+            ),
+            LogField::Processes => Value::Array(
+                sys.processes
+                    .iter()
+                    .filter(|p| p.thread_of.is_none())
+                    .map(export::process_json)
+                    .collect(),
+            ),
+        }
+    }
+}
+
+// appends a json line per refresh to a file, moved aside to <path>.1, <path>.2, ... once
+// it reaches max_bytes
+pub struct Logger {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+    keep_rotated: usize,
+    fields: Vec<LogField>,
+}
+
+fn open(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn rotated(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+
+    PathBuf::from(name)
+}
+
+impl Logger {
+    pub fn open(
+        path: &Path,
+        max_bytes: u64,
+        keep_rotated: usize,
+        fields: Vec<LogField>,
+    ) -> Result<Logger, String> {
+        let file = open(path).map_err(|e| format!("couldn't open {}: {}", path.display(), e))?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or_default();
+
+        Ok(Logger {
+            path: path.to_path_buf(),
+            file,
+            size,
+            max_bytes,
+            keep_rotated,
+            fields,
+        })
+    }
+
+    pub fn write(&mut self, sys: &SystemInfo) -> Result<(), String> {
+        let timestamp = util::format_timestamp_utc(SystemTime::now());
+        let entry = Value::object(
+            [("timestamp_utc", timestamp.into())]
+                .into_iter()
+                .chain(self.fields.iter().map(|f| (f.label(), f.value(sys)))),
+        );
+        let line = format!("{}\n", entry);
+
+        // an empty file takes the line whatever its size, so one huge entry can't rotate
+        // on every refresh
+        if self.size > 0 && self.size + line.len() as u64 > self.max_bytes {
+            self.rotate()
+                .map_err(|e| format!("couldn't rotate {}: {}", self.path.display(), e))?;
+        }
+
+        self.file
+            .write_all(line.as_bytes())
+            .map_err(|e| format!("couldn't write {}: {}", self.path.display(), e))?;
+        self.size += line.len() as u64;
+
+        Ok(())
+    }
+
+    // the oldest kept file is dropped and the rest move up one
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.keep_rotated == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            for n in (1..self.keep_rotated).rev() {
+                let from = rotated(&self.path, n);
+
+                if from.exists() {
+                    fs::rename(&from, rotated(&self.path, n + 1))?;
+                }
+            }
+
+            fs::rename(&self.path, rotated(&self.path, 1))?;
+        }
+
+        self.file = open(&self.path)?;
+        self.size = 0;
+
+        Ok(())
+    }
+}
@@ -1,340 +1,1144 @@
+mod alerts;
+mod app;
+mod baseline;
+#[cfg(feature = "bench")]
+mod bench;
+mod cli;
+#[cfg(feature = "clipboard")]
+mod clipboard;
+mod collector;
+mod columns;
+mod config;
+mod connections;
+#[cfg(feature = "desktop")]
+mod desktop;
+mod export;
+mod headless;
+mod history;
+// info collects more than the ui currently shows
+#[allow(dead_code)]
 mod info;
+mod json;
+mod labels;
+mod logger;
+mod privileges;
+mod procfs;
+mod session;
+mod sort;
+mod theme;
+mod ui;
+mod util;
+mod watch;
+mod widget;
 
 use crossterm::{
-    event::{self, KeyCode, KeyEventKind},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEventKind, KeyModifiers,
+        MouseEvent, MouseEventKind,
+    },
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
-use ratatui::{
-    layout::{Constraint, Direction, Layout}, prelude::{CrosstermBackend, Stylize, Terminal}, style::Style, widgets::{Block, Borders, Paragraph, Row, Table}
+use ratatui::prelude::{CrosstermBackend, Terminal};
+use std::{
+    any::Any,
+    fs,
+    io::{self, stdout, IsTerminal, Result, Write},
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
-use std::io::{stdout, Result};
 
-use itertools::Itertools;
+// how long the terminal size has to stay put before the next frame is drawn
+const RESIZE_SETTLE: Duration = Duration::from_millis(50);
+// a second c within this long copies the pid rather than the name
+const DOUBLE_COPY: Duration = Duration::from_millis(500);
+
+// restores the terminal when dropped, so an early return or a panic never leaves the shell in raw mode
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = stdout().execute(DisableMouseCapture);
+        let _ = stdout().execute(LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(feature = "desktop")]
+fn open_process_directory(process: &info::ProcessData) -> String {
+    use std::path::Path;
+
+    if !desktop::has_display() {
+        return "no desktop to open a file manager on".to_string();
+    }
+
+    // prefer the working directory, otherwise wherever the executable lives
+    let directory = process
+        .cwd
+        .as_deref()
+        .map(Path::new)
+        .or_else(|| process.exe_dir())
+        .filter(|d| !d.as_os_str().is_empty());
+
+    match directory {
+        Some(directory) => match desktop::open_in_file_manager(directory) {
+            Ok(()) => format!("opened {}", directory.display()),
+            Err(e) => format!("couldn't open {}: {}", directory.display(), e),
+        },
+        None => format!("no directory known for {}", process.name),
+    }
+}
+
+#[cfg(not(feature = "desktop"))]
+fn open_process_directory(_process: &info::ProcessData) -> String {
+    "pidwatch was built without the desktop feature".to_string()
+}
+
+// `what` names the text in the status message
+#[cfg(feature = "clipboard")]
+fn copy_to_clipboard(text: &str, what: &str) -> String {
+    match clipboard::copy(text) {
+        Ok(()) => format!("copied: {}", what),
+        Err(e) => format!("couldn't copy {}: {}", what, e),
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn copy_to_clipboard(_text: &str, _what: &str) -> String {
+    "pidwatch was built without the clipboard feature".to_string()
+}
+
+// set once a signal asks pidwatch to quit, the main loop then exits normally so the
+// terminal is restored. SIGHUP keeps its default, after a hangup there's no terminal left
+// to restore, and crossterm's poll spins on the dead tty rather than returning
+#[cfg(unix)]
+fn quit_signal() -> Result<Arc<AtomicBool>> {
+    use signal_hook::consts::{SIGINT, SIGTERM};
+
+    let received = Arc::new(AtomicBool::new(false));
+
+    for signal in [SIGINT, SIGTERM] {
+        signal_hook::flag::register(signal, Arc::clone(&received))?;
+    }
+
+    Ok(received)
+}
+
+#[cfg(not(unix))]
+fn quit_signal() -> Result<Arc<AtomicBool>> {
+    Ok(Arc::new(AtomicBool::new(false)))
+}
+
+// the status bar always shows an alert, the bell and desktop notification are opt-in
+fn raise_alert(message: &str, config: &config::Config) {
+    if config.alert_bell {
+        let mut out = stdout();
+        let _ = out.write_all(b"\x07").and_then(|_| out.flush());
+    }
+
+    if config.alert_notify {
+        notify_desktop(message);
+    }
+}
+
+#[cfg(feature = "desktop")]
+fn notify_desktop(message: &str) {
+    if desktop::has_display() {
+        let _ = desktop::notify("pidwatch", message);
+    }
+}
+
+#[cfg(not(feature = "desktop"))]
+fn notify_desktop(_message: &str) {}
+
+// while a search is being typed every key edits it, jumping to matches as they appear
+fn search_key(state: &mut app::AppState, code: KeyCode, rows: &[sort::ProcessGroup]) {
+    let Some(search) = &mut state.search else {
+        return;
+    };
+
+    match code {
+        KeyCode::Esc => state.search = None,
+        KeyCode::Enter => search.typing = false,
+        KeyCode::Backspace => {
+            search.query.pop();
+            state.next_match(rows, state.transient.selected_process);
+        }
+        KeyCode::Char(c) => {
+            search.query.push(c);
+            state.next_match(rows, state.transient.selected_process);
+        }
+        _ => {}
+    }
+}
+
+// a note is kept short enough to fit its column
+const NOTE_MAX_CHARS: usize = 60;
+
+// while a note is being typed every key edits it, an empty note removes the process's note
+fn note_key(state: &mut app::AppState, code: KeyCode) {
+    let Some(edit) = &mut state.note_edit else {
+        return;
+    };
+
+    match code {
+        KeyCode::Esc => state.note_edit = None,
+        KeyCode::Enter => {
+            let (pid, text) = (edit.pid, edit.text.trim().to_string());
+
+            if text.is_empty() {
+                state.notes.remove(&pid);
+            } else {
+                state.notes.insert(pid, text);
+            }
+
+            state.note_edit = None;
+        }
+        KeyCode::Backspace => {
+            edit.text.pop();
+        }
+        KeyCode::Char(c) if edit.text.chars().count() < NOTE_MAX_CHARS => edit.text.push(c),
+        _ => {}
+    }
+}
+
+// keys while the io priority popup is open
+fn ionice_key(state: &mut app::AppState, code: KeyCode) {
+    let Some(edit) = &mut state.ionice_edit else {
+        return;
+    };
+
+    let priority = &mut edit.priority;
+
+    match code {
+        KeyCode::Esc => state.ionice_edit = None,
+        KeyCode::Left | KeyCode::Right => {
+            priority.class = priority.class.step(code == KeyCode::Right)
+        }
+        KeyCode::Up => priority.level = priority.level.saturating_sub(1),
+        KeyCode::Down => priority.level = (priority.level + 1).min(procfs::IO_LEVEL_MAX),
+        KeyCode::Enter => {
+            let (pid, priority) = (edit.pid, edit.priority);
+
+            let message = match procfs::set_io_priority(pid, priority) {
+                Ok(()) => format!("io priority of {} set to {}", pid, priority.label()),
+                Err(e) => e,
+            };
+
+            // show what the kernel actually holds now
+            if let Some(detail) = &mut state.detail {
+                detail.refresh();
+            }
+
+            state.ionice_edit = None;
+            state.set_status(message);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(feature = "bench")]
+fn run_bench() {
+    bench::run();
+}
+
+#[cfg(not(feature = "bench"))]
+fn run_bench() {
+    eprintln!(
+        "pidwatch: --bench needs a build with the bench feature (cargo build --features bench)"
+    );
+    std::process::exit(2);
+}
+
+// a click focuses the panel under it and the wheel scrolls it, whichever is focused
+fn mouse_event(
+    state: &mut app::AppState,
+    mouse: MouseEvent,
+    sys: &info::SystemInfo,
+    process_rows: usize,
+) {
+    let Some(panel) = state.panel_at(mouse.column, mouse.row) else {
+        return;
+    };
+
+    match (mouse.kind, panel) {
+        (MouseEventKind::Down(_), _) => state.focused = panel,
+        (MouseEventKind::ScrollUp, app::Panel::Cpu) => {
+            let offset = state.transient.cpu_scroll_offset.saturating_sub(1);
+            state.scroll_cpus(offset, sys.usage.cpus.len());
+        }
+        (MouseEventKind::ScrollDown, app::Panel::Cpu) => {
+            let offset = state.transient.cpu_scroll_offset + 1;
+            state.scroll_cpus(offset, sys.usage.cpus.len());
+        }
+        (MouseEventKind::ScrollUp, app::Panel::Specs) => {
+            state.transient.specs_scroll = state.transient.specs_scroll.saturating_sub(1)
+        }
+        (MouseEventKind::ScrollDown, app::Panel::Specs) => {
+            state.transient.specs_scroll =
+                (state.transient.specs_scroll + 1).min(state.transient.specs_overflow.get().0)
+        }
+        (MouseEventKind::ScrollUp, app::Panel::Processes) => {
+            state.transient.selected_process = state.transient.selected_process.saturating_sub(1)
+        }
+        (MouseEventKind::ScrollDown, app::Panel::Processes) => {
+            state.transient.selected_process =
+                (state.transient.selected_process + 1).min(process_rows.saturating_sub(1))
+        }
+        _ => {}
+    }
+}
+
+// keys while the disk sort menu is open
+fn disk_sort_key(state: &mut app::AppState, code: KeyCode) {
+    let Some(highlighted) = state.disk_sort_menu else {
+        return;
+    };
+
+    let count = sort::DiskSorter::ALL.len();
+
+    match code {
+        KeyCode::Esc | KeyCode::Char('d') => state.disk_sort_menu = None,
+        KeyCode::Up => state.disk_sort_menu = Some((highlighted + count - 1) % count),
+        KeyCode::Down => state.disk_sort_menu = Some((highlighted + 1) % count),
+        KeyCode::Enter => {
+            state.disk_sorter = sort::DiskSorter::ALL[highlighted];
+            state.disk_sort_menu = None;
+        }
+        _ => {}
+    }
+}
+
+// keys while the process table's columns are being edited, enter saves them to the config
+// file and escape puts them back as they were
+fn column_key(
+    state: &mut app::AppState,
+    code: KeyCode,
+    sys: &info::SystemInfo,
+    config_path: Option<PathBuf>,
+) {
+    let Some(edit) = &state.column_edit else {
+        return;
+    };
+
+    let selected = edit.selected;
+    let visible = state.visible_columns(sys);
+    let position = visible.iter().position(|c| *c == selected).unwrap_or_default();
+
+    match code {
+        KeyCode::Esc => {
+            if let Some(edit) = state.column_edit.take() {
+                state.columns = edit.original;
+            }
+        }
+        KeyCode::Enter => {
+            state.column_edit = None;
+
+            let message = match config::Config::save_columns(config_path, &state.columns) {
+                Ok(path) => format!("columns saved to {}", path.display()),
+                Err(e) => e,
+            };
+
+            state.set_status(message);
+        }
+        KeyCode::Left | KeyCode::Right => {
+            let next = if code == KeyCode::Right {
+                visible.get(position + 1)
+            } else {
+                position.checked_sub(1).and_then(|p| visible.get(p))
+            };
+
+            if let (Some(edit), Some(&next)) = (&mut state.column_edit, next) {
+                edit.selected = next;
+            }
+        }
+        KeyCode::Char('+') | KeyCode::Char('=') => state.columns.resize(selected, true),
+        KeyCode::Char('-') => state.columns.resize(selected, false),
+        KeyCode::Char('<') => state.columns.move_column(selected, false, &visible),
+        KeyCode::Char('>') => state.columns.move_column(selected, true, &visible),
+        _ => {}
+    }
+}
 
 fn main() -> Result<()> {
+    let args = match cli::Args::parse() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("pidwatch: {}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if args.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+
+    if args.bench {
+        run_bench();
+        return Ok(());
+    }
+
+    if args.platform {
+        let platform = info::platforms::current_platform();
+        let supported = platform.supported_features();
+
+        println!("{}:", platform.label());
+
+        for feature in info::platforms::Feature::ALL {
+            let shown = if supported.contains(&feature) {
+                "yes"
+            } else {
+                "no"
+            };
+
+            println!("    {:<44}{}", feature.label(), shown);
+        }
+
+        return Ok(());
+    }
+
+    if args.list_baselines {
+        match baseline::list_named() {
+            Ok(named) if named.is_empty() => {
+                println!("no named baselines, save one with --baseline <name>")
+            }
+            Ok(named) => {
+                for (name, saved) in named {
+                    println!("{}  {}", util::format_timestamp_utc(saved), name);
+                }
+            }
+            Err(e) => {
+                eprintln!("pidwatch: {}", e);
+                std::process::exit(2);
+            }
+        }
+
+        return Ok(());
+    }
+
+    let baseline_file = match args.baseline.as_deref().map(baseline::named_path).transpose() {
+        Ok(named) => named.or_else(|| args.baseline_file.clone()),
+        Err(e) => {
+            eprintln!("pidwatch: {}", e);
+            std::process::exit(2);
+        }
+    };
+
+    let switch_user = |name: &str| {
+        if let Err(e) = privileges::switch_user(name) {
+            eprintln!("pidwatch: {}", e);
+            std::process::exit(2);
+        }
+    };
+
+    if let Some(name) = &args.user {
+        switch_user(name);
+    }
+
+    if let Some((condition, clear, interval)) = args.watch.clone() {
+        let quit_requested = quit_signal()?;
+
+        // like --alert-net-errors, a breach is reported with the exit status
+        if watch::run(condition, clear, interval, &quit_requested)? {
+            std::process::exit(1);
+        }
+
+        return Ok(());
+    }
+
+    if let Some(log_path) = &args.headless_monitor {
+        headless::run(
+            log_path,
+            args.headless_interval,
+            args.headless_pid_file.as_deref(),
+            args.alert_net_errors,
+        )?;
+
+        // it only returns once the network error limit is passed
+        std::process::exit(1);
+    }
+
+    if let Some(path) = &args.export_csv {
+        let mut sys = info::SystemInfo::new();
+        sys.populate();
+
+        let reporter = export::CsvReporter::new();
+
+        return if path.as_os_str() == "-" {
+            reporter.write_streaming(sys.processes.iter(), stdout().lock())
+        } else {
+            let file = io::BufWriter::new(fs::File::create(path)?);
+            reporter.write_streaming(sys.processes.iter(), file)
+        };
+    }
+
+    if let Some(path) = &args.stream_json {
+        let mut sys = info::SystemInfo::new();
+        sys.populate();
+
+        return if path.as_os_str() == "-" {
+            export::write_json_streaming(&sys, io::BufWriter::new(stdout().lock()))
+        } else {
+            export::write_json_streaming(&sys, io::BufWriter::new(fs::File::create(path)?))
+        };
+    }
+
+    if args.health_json {
+        let mut sys = info::SystemInfo::new();
+        sys.populate();
+
+        let components = sys.health_components();
+        // one decimal, f32 widened to f64 prints a tail of noise
+        let percent = |p: f32| json::Value::from((p as f64 * 10.0).round() / 10.0);
+        let health = json::Value::object([
+            ("score", components.score().round().into()),
+            (
+                "components",
+                json::Value::object([
+                    ("cpu", percent(components.cpu)),
+                    ("memory", percent(components.memory)),
+                    ("swap", percent(components.swap)),
+                    ("disk", percent(components.disk)),
+                    ("network_errors", percent(components.network_errors)),
+                ]),
+            ),
+        ]);
+
+        println!("{}", health);
+        return Ok(());
+    }
+
+    let config = match config::Config::load(args.config.clone()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("pidwatch: {}", e);
+            std::process::exit(2);
+        }
+    };
+
+    // redirected to a file or a pipe the tui would only leave escape codes in it, the widget's
+    // line per refresh is the closest thing that reads well there
+    let tui_possible = stdout().is_terminal();
+
+    // the widget keeps no log or baseline, quietly carrying on without them would lose them
+    let tui_only_flag = [
+        (args.log.is_some(), "--log"),
+        (args.baseline.is_some(), "--baseline"),
+        (args.baseline_file.is_some(), "--baseline-file"),
+    ]
+    .into_iter()
+    .find_map(|(given, flag)| given.then_some(flag));
+
+    if let Some(flag) = tui_only_flag.filter(|_| args.widget || !tui_possible) {
+        if args.widget {
+            eprintln!("pidwatch: {} only works with the tui, not --widget", flag);
+        } else {
+            eprintln!(
+                "pidwatch: stdout isn't a terminal, and {} only works with the tui",
+                flag
+            );
+        }
+
+        std::process::exit(2);
+    }
+
+    if !args.widget && !tui_possible {
+        eprintln!(
+            "pidwatch: stdout isn't a terminal, printing a summary line every refresh instead \
+             of the tui (as with --widget)"
+        );
+    }
+
+    if args.widget || !tui_possible {
+        // the widget reads everything itself, so there's nothing to read as root first
+        if let Some(name) = &args.drop_privileges {
+            switch_user(name);
+        }
+
+        let quit_requested = quit_signal()?;
+        return widget::run(config.cpu_interval, &quit_requested);
+    }
+
     let mut sys = info::SystemInfo::new();
     sys.populate();
 
+    if let Some(name) = &args.drop_privileges {
+        switch_user(name);
+    }
+
+    // the view is picked up where the last run left it
+    let session_path = session::default_path();
+    let mut state = match session_path.as_deref().map(session::load) {
+        Some(Ok(Some(persistent))) => app::AppState::from(persistent),
+        Some(Err(e)) => {
+            let mut state = app::AppState::default();
+            state.set_status(format!("couldn't restore the last view, {}", e));
+            state
+        }
+        _ => app::AppState::default(),
+    };
+
+    if let Some(mode) = args.perf_mode {
+        state.performance_mode = mode;
+    }
+
+    state.age_buckets = config.process_age_buckets.clone();
+    state.clock_stats = config.clock_speed.clone();
+    state.memory_highlight = config.memory_highlight;
+    state.process_state_summary = sys.process_state_counts();
+
+    // a [columns] section in the config wins over the layout the last run left
+    if let Some(columns) = &config.columns {
+        state.columns = columns.clone();
+    }
+
+    state.baseline_name = args.baseline.clone();
+
+    if let Some(path) = baseline_file.as_deref().filter(|p| p.exists()) {
+        match baseline::load(path) {
+            Ok((loaded, history)) => {
+                state.baseline = Some(Box::new(loaded));
+                state.baseline_history = history;
+                state.compare_with_baseline(&sys);
+            }
+            Err(e) => {
+                eprintln!("pidwatch: {}", e);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let logger = args
+        .log
+        .as_deref()
+        .map(|path| {
+            logger::Logger::open(
+                path,
+                args.log_max_mb.saturating_mul(1024 * 1024),
+                args.log_keep_rotated,
+                args.log_fields.clone(),
+            )
+        })
+        .transpose()
+        .unwrap_or_else(|e| {
+            eprintln!("pidwatch: {}", e);
+            std::process::exit(2);
+        });
+
     stdout().execute(EnterAlternateScreen)?;
+    stdout().execute(EnableMouseCapture)?;
     enable_raw_mode()?;
+    let _guard = TerminalGuard;
+
+    // the terminal's background is asked for on every start, unless the config names a theme
+    state.theme = config.theme.unwrap_or_else(theme::Theme::detect);
+
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
     terminal.clear()?;
 
 
-    let network_order = sys
-        .usage
-        .networks
-        .iter()
-        .map(|n| n.name.clone())
-        .collect::<Vec<String>>();
+    let mut network_order = vec![];
+    sys.usage.extend_network_order(&mut network_order);
+
+    let quit_requested = quit_signal()?;
+    let collector = collector::Collector::spawn(sys.clone(), &config, logger);
+    collector.set_mode(state.performance_mode);
+    collector.count_connections(state.show_connection_count);
+    let mut last_request: Option<Instant> = None;
+    let mut process_rows = state.process_rows(&sys).len();
+    let mut alerts = alerts::Alerts::new();
+    // saved right after the next frame is drawn
+    let mut pending_export: Option<export::Format> = None;
+    // the last resize of a burst still settling
+    let mut resized_at: Option<Instant> = None;
+    // set when --alert-net-errors ends the loop
+    let mut net_errors_exceeded = false;
 
     loop {
-        sys.populate();
+        if quit_requested.load(Ordering::Relaxed) {
+            break;
+        }
 
-        // four sections: specs, processes, usage (ram, cpu, disk, swap), network
-        // each section has an expandable view, (s, p, u, n)
-        // by default, the usage section is expanded
+        let refresh_interval = state
+            .performance_mode
+            .refresh_interval(config.refresh_tick());
 
-        if event::poll(std::time::Duration::from_millis(16))? {
-            if let event::Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Char('q') => break,
-                        KeyCode::Esc => break,
-                        _ => {}
+        if !state.paused && last_request.is_none_or(|t| t.elapsed() >= refresh_interval) {
+            collector.request();
+            last_request = Some(Instant::now());
+        }
+
+        // a refresh already on its way is dropped once paused
+        if let Some(refresh) = collector.latest().filter(|_| !state.paused) {
+            let previous = std::mem::replace(&mut sys, refresh.info);
+            sys.usage.extend_network_order(&mut network_order);
+
+            if let Some(e) = refresh.log_error {
+                state.set_status(e);
+            }
+
+            if let Some(counts) = refresh.connection_counts {
+                state.connection_counts = counts;
+            }
+
+            for subsystem in refresh.refreshed {
+                // from the full list, whatever the table is showing
+                if subsystem == info::Subsystem::Processes {
+                    state.process_state_summary = sys.process_state_counts();
+                }
+
+                state.refreshed_at.insert(subsystem, Instant::now());
+            }
+
+            state.compare_with_baseline(&sys);
+
+            if refresh.full {
+                state.follow_core_spikes(&previous, &sys);
+                state.slow_refresh = (refresh.took > refresh_interval).then_some(refresh.took);
+                state.record_history(&sys);
+                state.track_network_errors(&previous, &sys);
+                state.forget_exited_notes(&sys);
+
+                if args
+                    .alert_net_errors
+                    .is_some_and(|limit| sys.usage.total_network_errors() > limit)
+                {
+                    net_errors_exceeded = true;
+                    break;
+                }
+
+                if let Some(detail) = &mut state.detail {
+                    detail.refresh();
+                }
+
+                for message in alerts.check(&sys, &config) {
+                    raise_alert(&message, &config);
+
+                    // the first alert is the one kept, it's what the state was frozen for
+                    if config.alert_pause && state.paused_by.is_none() {
+                        state.paused = true;
+                        state.paused_by = Some(message.clone());
                     }
+
+                    state.set_status(message);
                 }
             }
+
+            // processes come and go, keep the selection on an existing row
+            process_rows = state.process_rows(&sys).len();
+            state.transient.selected_process =
+                state.transient.selected_process.min(process_rows.saturating_sub(1));
         }
 
-        // now match the widget and display the appropriate section
-        // here, have a title bar, for example "Windows 10" or "Debian 13"
-        // then have a list of specs, like "Hostname: <hostname>"
+        // four sections: specs, processes, usage (ram, cpu, disk, swap), network
+        // each section has an expandable view, (s, p, u, n)
+        // by default, the usage section is expanded
 
-        let _ = terminal.draw(|frame| {
-            let main_layout = Layout::new(
-                Direction::Vertical,
-                [
-                    Constraint::Length(1),
-                    Constraint::Min(0),
-                    Constraint::Length(1),
-                ],
-            )
-            .split(frame.size());
-            frame.render_widget(
-                Block::new()
-                    .borders(Borders::TOP)
-                    .title(format!("{} {}", sys.spec.os, sys.spec.kernel))
-                    .bold(),
-                main_layout[0],
-            );
-            frame.render_widget(
-                Block::new().borders(Borders::TOP).title("pidwatch").bold(),
-                main_layout[2],
-            );
+        let mut timeout = state.performance_mode.frame_interval();
 
-            let inner_layout = Layout::new(
-                Direction::Horizontal,
-                [Constraint::Percentage(50), Constraint::Percentage(50)],
-            )
-            .split(main_layout[1]);
+        // wake up in time to draw once a resize has settled
+        if let Some(at) = resized_at {
+            timeout = timeout.min(RESIZE_SETTLE.saturating_sub(at.elapsed()));
+        }
 
-            let left_layout = Layout::new(
-                Direction::Vertical,
-                [Constraint::Percentage(50), Constraint::Percentage(50)],
-            )
-            .split(inner_layout[0]);
+        if event::poll(timeout)? {
+            let event = event::read()?;
 
-            let right_layout = Layout::new(
-                Direction::Vertical,
-                [Constraint::Percentage(50), Constraint::Percentage(50)],
-            )
-            .split(inner_layout[1]);
+            if let event::Event::Resize(..) = event {
+                resized_at = Some(Instant::now());
+            }
 
-            // now split each column into two rows, giving us a 2x2 grid
-            frame.render_widget(
-                Block::default().borders(Borders::ALL).title("CPU").yellow(),
-                left_layout[0],
-            );
-            frame.render_widget(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Memory")
-                    .blue(),
-                left_layout[1],
-            );
+            // the detail view sits over the panels, clicks aren't meant for them
+            if let event::Event::Mouse(mouse) = event {
+                if state.detail.is_none() {
+                    mouse_event(&mut state, mouse, &sys, process_rows);
+                }
+            }
 
-            frame.render_widget(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Specs/Network")
-                    .red(),
-                right_layout[0],
-            );
-            frame.render_widget(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Processes")
-                    .magenta(),
-                right_layout[1],
-            );
+            if let event::Event::Key(key) = event {
+                let typing = state.search.as_ref().is_some_and(|s| s.typing);
+                // raw mode turns ctrl-c into a key press rather than SIGINT
+                let ctrl_c = key.code == KeyCode::Char('c')
+                    && key.modifiers.contains(KeyModifiers::CONTROL);
 
-            // now we can render the actual data
-
-            // first, create four handles, to each paragraph widget
-            let top_left_inner = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([
-                    Constraint::Length(1),
-                    Constraint::Length(2),
-                    Constraint::Min(1),
-                ])
-                .split(left_layout[0]);
-
-            let top_right_inner = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([Constraint::Min(1)])
-                .split(right_layout[0]);
-
-            let top_right_inner_inner = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([
-                    Constraint::Length(1),
-                    Constraint::Min(1),
-                    Constraint::Min(1),
-                ])
-                .split(top_right_inner[0]);
-
-            let bottom_left_inner = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([Constraint::Length(1), Constraint::Min(1)])
-                .split(left_layout[1]);
-
-            let bottom_right_inner = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([Constraint::Length(1), Constraint::Min(1)])
-                .split(right_layout[1]);
-
-            // now we can render the actual data
-
-            let formatted_cpu = format!(
-                "Average Usage: {:.2}%\nAverage Clock Speed: {:.2} GHz\n\n",
-                // average the usage of all cpus
-                sys.usage.cpus.iter().map(|c| c.usage).sum::<f32>() / sys.usage.cpus.len() as f32,
-                // average the clock speed of all cpus
-                sys.usage.cpus.iter().map(|c| c.clock_speed).sum::<f32>()
-                    / sys.usage.cpus.len() as f32,
-            );
+                if key.kind == KeyEventKind::Press && ctrl_c {
+                    if config.ctrl_c_quits || state.ctrl_c() {
+                        break;
+                    }
+                } else if key.kind == KeyEventKind::Press && typing {
+                    let rows = state.process_rows(&sys);
+                    search_key(&mut state, key.code, &rows);
+                } else if key.kind == KeyEventKind::Press && state.note_edit.is_some() {
+                    note_key(&mut state, key.code);
+                } else if key.kind == KeyEventKind::Press && state.ionice_edit.is_some() {
+                    ionice_key(&mut state, key.code);
+                } else if key.kind == KeyEventKind::Press && state.disk_sort_menu.is_some() {
+                    disk_sort_key(&mut state, key.code);
+                } else if key.kind == KeyEventKind::Press && state.column_edit.is_some() {
+                    column_key(&mut state, key.code, &sys, args.config.clone());
+                } else if key.kind == KeyEventKind::Press {
+                    match key.code {
+                        // escape closes whatever is open before it closes pidwatch
+                        KeyCode::Esc if state.detail.is_some() => state.detail = None,
+                        KeyCode::Esc if state.search.is_some() => state.search = None,
+                        KeyCode::Char('q') => break,
+                        KeyCode::Esc => break,
+                        KeyCode::Char(c @ '1'..='3') => {
+                            state.performance_mode = match c {
+                                '1' => app::PerformanceMode::Low,
+                                '2' => app::PerformanceMode::Normal,
+                                _ => app::PerformanceMode::High,
+                            };
 
-            let mut formatted_core_data = String::from("\n");
+                            collector.set_mode(state.performance_mode);
+                        }
+                        KeyCode::Char('a') => state.relative_cpu = !state.relative_cpu,
+                        KeyCode::Char('A') => state.aggregation = state.aggregation.toggle(),
+                        KeyCode::Char('s') => {
+                            state.sorter = state.sorter.next();
+                            state.sort_reversed = false;
 
-            for cpu in &sys.usage.cpus {
-                formatted_core_data.push_str(&format!(
-                    "{} ({:.2}%) at {:.2} GHz ({})\n",
-                    cpu.name, cpu.usage, cpu.clock_speed, cpu.vendor,
-                ));
-            }
+                            // picking a new sort counts as asking for a re-sort
+                            if state.frozen_order.is_some() {
+                                state.freeze_order(&sys);
+                            }
+                        }
+                        KeyCode::F(number) if config.sort_keys.contains_key(&number) => {
+                            state.sort_by(config.sort_keys[&number]);
 
-            frame.render_widget(Paragraph::new(formatted_cpu).bold(), top_left_inner[1]);
+                            if state.frozen_order.is_some() {
+                                state.freeze_order(&sys);
+                            }
+                        }
+                        KeyCode::Char(' ') => {
+                            state.paused = !state.paused;
+                            state.paused_by = None;
+                        }
+                        KeyCode::Char('z') if state.frozen_order.is_some() => {
+                            state.frozen_order = None
+                        }
+                        KeyCode::Char('z') => state.freeze_order(&sys),
+                        KeyCode::Char('r') if state.frozen_order.is_some() => {
+                            state.freeze_order(&sys);
+                            state.set_status("re-sorted");
+                        }
+                        KeyCode::Char('f') => {
+                            state.search = Some(app::Search {
+                                query: String::new(),
+                                typing: true,
+                            })
+                        }
+                        KeyCode::Char('n') if state.search.is_some() => {
+                            let rows = state.process_rows(&sys);
+                            state.next_match(&rows, state.transient.selected_process + 1);
+                        }
+                        KeyCode::Char('N') if state.search.is_some() => {
+                            let rows = state.process_rows(&sys);
+                            state.previous_match(&rows);
+                        }
+                        KeyCode::Char('N') => state.show_nice = !state.show_nice,
+                        KeyCode::Char('g') => {
+                            state.toggle_growth();
 
-            frame.render_widget(Paragraph::new(formatted_core_data), top_left_inner[2]);
+                            if state.frozen_order.is_some() {
+                                state.freeze_order(&sys);
+                            }
+                        }
+                        KeyCode::Char('c') => {
+                            let processes = state.process_rows(&sys);
 
-            let uptime_days = sys.spec.uptime.parse::<f32>().unwrap_or_default() / 86400.0;
+                            if let Some(row) = processes.get(state.transient.selected_process) {
+                                let twice =
+                                    state.last_copy.is_some_and(|t| t.elapsed() < DOUBLE_COPY);
+                                // a grouped row copies every pid in it, ready for kill
+                                let text = if twice {
+                                    row.pids
+                                        .iter()
+                                        .map(u32::to_string)
+                                        .collect::<Vec<String>>()
+                                        .join(" ")
+                                } else {
+                                    row.process.name.clone()
+                                };
 
-            let uptime_hours = (uptime_days - uptime_days.floor()) * 24.0;
+                                state.last_copy = (!twice).then(Instant::now);
+                                state.set_status(copy_to_clipboard(&text, &text));
+                            }
+                        }
+                        KeyCode::Char('M') => {
+                            let rows = state.process_rows(&sys);
+                            let summary = export::markdown_summary(&sys, &rows);
 
-            let uptime_minutes = (uptime_hours - uptime_hours.floor()) * 60.0;
+                            state.set_status(copy_to_clipboard(&summary, "markdown summary"));
+                        }
+                        KeyCode::Char('C') => {
+                            state.show_connection_count = !state.show_connection_count;
+                            collector.count_connections(state.show_connection_count);
+                        }
+                        KeyCode::Char('i') => {
+                            if let Some(detail) = &state.detail {
+                                // start an unset priority from what it behaves like, not 0
+                                let nice = sys
+                                    .processes
+                                    .iter()
+                                    .find(|p| p.pid == detail.pid)
+                                    .map_or(0, |p| p.nice);
+                                let priority = match detail.extras.io_priority {
+                                    Some(p) if p.class != procfs::IoClass::None => p,
+                                    _ => procfs::IoPriority::from_nice(nice),
+                                };
 
-            let uptime_seconds = (uptime_minutes - uptime_minutes.floor()) * 60.0;
+                                state.ionice_edit = Some(app::IoniceEdit {
+                                    pid: detail.pid,
+                                    priority,
+                                });
+                            }
+                        }
+                        KeyCode::Left | KeyCode::Right if state.detail.is_some() => {
+                            if let Some(detail) = &mut state.detail {
+                                detail.tab = detail.tab.step(key.code == KeyCode::Right);
+                            }
+                        }
+                        KeyCode::Char('T') => {
+                            state.state_display = app::StateDisplay::next(state.state_display)
+                        }
+                        KeyCode::Char('P') => state.show_sched_policy = !state.show_sched_policy,
+                        KeyCode::Char('H') => state.merge_threads = !state.merge_threads,
+                        KeyCode::Char('L') => state.start_column_edit(&sys),
+                        KeyCode::Char('v') => state.labels = state.labels.toggle(),
+                        KeyCode::Char('u') => state.units = state.units.next(),
+                        KeyCode::Char('m') => {
+                            let rows = state.process_rows(&sys);
 
-            let formatted_uptime = format!(
-                "{}d {}h {}m {}s ({}s)",
-                uptime_days.floor(),
-                uptime_hours.floor(),
-                uptime_minutes.floor(),
-                uptime_seconds.floor(),
-                sys.spec.uptime
-            );
+                            // the member whose note the row shows, or the busiest if none has one
+                            if let Some(row) = rows.get(state.transient.selected_process) {
+                                let pid = state.note_pid(&row.pids).unwrap_or(row.process.pid);
 
-            // now, we move on to system specs
-            let formatted_specs = format!(
-                "Hostname: {}
-OS: {}
-Kernel: {}
-Uptime: {}
-Users: {}",
-                sys.spec.hostname,
-                sys.spec.os,
-                sys.spec.kernel,
-                formatted_uptime,
-                // users where uid > 1000
-                sys.spec
-                    .users
-                    .iter()
-                    .filter(|u| u.uid.parse::<u32>().unwrap_or_default() > 1000)
-                    .map(|u| u.name.clone())
-                    .collect::<Vec<String>>()
-                    .len(),
-            );
+                                state.note_edit = Some(app::NoteEdit {
+                                    pid,
+                                    text: state.notes.get(&pid).cloned().unwrap_or_default(),
+                                });
+                            }
+                        }
+                        KeyCode::Char('d') => {
+                            state.disk_sort_menu = sort::DiskSorter::ALL
+                                .iter()
+                                .position(|s| *s == state.disk_sorter)
+                        }
+                        KeyCode::Tab => state.toggle_focus(&sys),
+                        KeyCode::Up if state.focused == app::Panel::Cpu => {
+                            let offset = state.transient.cpu_scroll_offset.saturating_sub(1);
+                            state.scroll_cpus(offset, sys.usage.cpus.len());
+                        }
+                        KeyCode::Down if state.focused == app::Panel::Cpu => {
+                            let offset = state.transient.cpu_scroll_offset + 1;
+                            state.scroll_cpus(offset, sys.usage.cpus.len());
+                        }
+                        KeyCode::Home if state.focused == app::Panel::Cpu => {
+                            state.scroll_cpus(0, sys.usage.cpus.len())
+                        }
+                        KeyCode::End if state.focused == app::Panel::Cpu => {
+                            state.scroll_cpus(usize::MAX, sys.usage.cpus.len())
+                        }
+                        KeyCode::Up if state.focused == app::Panel::Specs => {
+                            state.transient.specs_scroll =
+                                state.transient.specs_scroll.saturating_sub(1)
+                        }
+                        KeyCode::Down if state.focused == app::Panel::Specs => {
+                            state.transient.specs_scroll =
+                                (state.transient.specs_scroll + 1)
+                                    .min(state.transient.specs_overflow.get().0)
+                        }
+                        KeyCode::Left if state.focused == app::Panel::Specs => {
+                            state.transient.network_scroll =
+                                state.transient.network_scroll.saturating_sub(1)
+                        }
+                        KeyCode::Right if state.focused == app::Panel::Specs => {
+                            state.transient.network_scroll =
+                                (state.transient.network_scroll + 1)
+                                    .min(state.transient.specs_overflow.get().1)
+                        }
+                        KeyCode::Home if state.focused == app::Panel::Specs => {
+                            state.transient.specs_scroll = 0;
+                            state.transient.network_scroll = 0;
+                        }
+                        KeyCode::Up => {
+                            state.transient.selected_process =
+                                state.transient.selected_process.saturating_sub(1)
+                        }
+                        KeyCode::Down => {
+                            state.transient.selected_process =
+                                (state.transient.selected_process + 1)
+                                    .min(process_rows.saturating_sub(1))
+                        }
+                        KeyCode::Enter => {
+                            let processes = state.process_rows(&sys);
 
-            frame.render_widget(
-                Paragraph::new(formatted_specs).bold(),
-                top_right_inner_inner[1],
-            );
+                            if let Some(row) = processes.get(state.transient.selected_process) {
+                                state.detail = Some(app::ProcessDetail::new(row.pids.clone()));
+                            }
+                        }
+                        KeyCode::Char('o') => {
+                            let processes = state.process_rows(&sys);
 
-            // now onto memory
-            let formatted_ram = format!(
-                "Used: {:.2} GB\nTotal: {:.2} GB",
-                sys.usage.memory as f32 / 1024.0 / 1024.0 / 1024.0,
-                sys.usage.total_memory as f32 / 1024.0 / 1024.0 / 1024.0,
-            );
+                            if let Some(row) = processes.get(state.transient.selected_process) {
+                                let message = open_process_directory(&row.process);
+                                state.set_status(message);
+                            }
+                        }
+                        KeyCode::Char('b') if state.baseline.is_some() => {
+                            state.baseline = None;
+                            state.baseline_history.clear();
+                            state.baseline_report = None;
+                            state.show_baseline = false;
+                            state.set_status("baseline cleared");
+                        }
+                        KeyCode::Char('b') => {
+                            state.baseline = Some(Box::new(sys.clone()));
+                            state.baseline_history = state.network_history.clone();
+                            state.compare_with_baseline(&sys);
 
-            let formatted_swap = format!(
-                "Used: {:.2} GB\nTotal: {:.2} GB",
-                sys.usage.swap as f32 / 1024.0 / 1024.0 / 1024.0,
-                sys.usage.total_swap as f32 / 1024.0 / 1024.0 / 1024.0,
-            );
+                            let message = match &baseline_file {
+                                Some(path) => {
+                                    match baseline::save(&sys, &state.baseline_history, path) {
+                                        Ok(()) => format!("baseline saved to {}", path.display()),
+                                        Err(e) => e,
+                                    }
+                                }
+                                None => "baseline taken, B compares against it".to_string(),
+                            };
 
-            let formatted_disk = format!(
-                "Used: {:.2} GB\nTotal: {:.2} GB",
-                sys.usage.disks.iter().map(|d| d.used).sum::<u64>() as f32
-                    / 1024.0
-                    / 1024.0
-                    / 1024.0,
-                sys.usage.disks.iter().map(|d| d.total).sum::<u64>() as f32
-                    / 1024.0
-                    / 1024.0
-                    / 1024.0,
-            );
+                            state.set_status(message);
+                        }
+                        KeyCode::Char('B') if state.baseline.is_none() => {
+                            state.set_status("no baseline yet, take one with b")
+                        }
+                        KeyCode::Char('B') => state.show_baseline = !state.show_baseline,
+                        KeyCode::Char('x') => pending_export = Some(export::Format::Ansi),
+                        KeyCode::Char('X') => pending_export = Some(export::Format::Html),
+                        KeyCode::Char('w') => {
+                            let processes = state.process_rows(&sys);
+                            let selected = processes.get(state.transient.selected_process);
 
-            frame.render_widget(
-                Paragraph::new(format!(
-                    "RAM:\n{}\n\nSWAP:\n{}\n\nDISK:\n{}\n",
-                    formatted_ram, formatted_swap, formatted_disk
-                ))
-                .bold(),
-                bottom_left_inner[1],
-            );
+                            // watching the already watched process again stops watching it,
+                            // a group can only be watched through its busiest member
+                            state.watched_pid = match selected {
+                                Some(row) if state.watched_pid == row.pids.first().copied() => None,
+                                Some(row) => {
+                                    if row.pid().is_none() {
+                                        state.set_status(format!(
+                                            "watching {}, the busiest of {} named {}",
+                                            row.process.pid,
+                                            row.pids.len(),
+                                            row.process.name
+                                        ));
+                                    }
 
-            // now, network
-            let mut formatted_network = String::new();
+                                    row.pids.first().copied()
+                                }
+                                None => None,
+                            };
 
-            let raw_networks = sys.usage.networks.clone();
+                            collector.watch(state.watched_pid);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
 
-            let ordered_networks = network_order
-                .iter()
-                .map(|n| raw_networks.iter().find(|x| x.name == *n).unwrap())
-                .collect::<Vec<&info::Network>>();
+        // now match the widget and display the appropriate section
+        // here, have a title bar, for example "Windows 10" or "Debian 13"
+        // then have a list of specs, like "Hostname: <hostname>"
 
-            for network in ordered_networks {
-                formatted_network.push_str(&format!(
-                    "Name: {}\nMAC: {}\nSent/Recieved: {}B/{}B\n\n",
-                    network.name,
-                    network.mac,
-                    network.total_sent,
-                    network.total_recv,
-                ));
+        // dragging a window edge sends a burst of resizes, drawing each one flickers and can
+        // leave half drawn frames behind, so draw once from a clean screen when they stop
+        if let Some(at) = resized_at {
+            if at.elapsed() < RESIZE_SETTLE {
+                continue;
             }
 
-            frame.render_widget(
-                Paragraph::new(formatted_network).bold(),
-                top_right_inner_inner[2],
-            );
+            resized_at = None;
+            terminal.clear()?;
+        }
 
-            // now for the big one, processes
-            // this will be a table, with the headers being "PID", "Name", "CPU", "Memory", "Uptime"
-            // importantly, the table will be sorted by CPU usage
+        let had_error = state.transient.last_render_error.is_some();
+        let size = terminal.size()?;
+        let too_small = size.width < ui::MIN_WIDTH || size.height < ui::MIN_HEIGHT;
 
-            let mut rows = vec![Row::new(vec!["PID", "Name", "CPU", "Memory", "Uptime"]).style(Style::new().on_red())];
+        // big enough again, wipe the placeholder so the panels are drawn from a clean screen
+        if state.transient.too_small && !too_small {
+            terminal.clear()?;
+        }
 
-            let sorted_by_cpu = sys
-                .processes
-                .clone()
-                .into_iter()
-                .sorted_by(|a, b| b.cpu_usage.partial_cmp(&a.cpu_usage).unwrap());
+        state.transient.too_small = too_small;
 
-            // now sum any processes with the same name together
-            let mut summed_processes: Vec<crate::info::ProcessData> = vec![];
+        let completed = terminal.draw(|frame| {
+            if state.transient.too_small {
+                ui::render_too_small(frame);
+                return;
+            }
 
-            for process in sorted_by_cpu {
-                if let Some(existing) = summed_processes.iter_mut().find(|p| p.name == process.name)
-                {
-                    existing.cpu_usage += process.cpu_usage;
-                    existing.ram += process.ram;
-                    existing.total_time += process.total_time;
-                } else {
-                    summed_processes.push(process);
-                }
+            if let Some(error) = &state.transient.last_render_error {
+                ui::render_error(frame, error);
+                return;
             }
 
-            for process in summed_processes {
-                rows.push(Row::new(vec![
-                    process.pid.to_string(),
-                    process.name.clone(),
-                    format!("{:.2}%", process.cpu_usage),
-                    format!("{:.2} MB", process.ram as f32 / 1024.0 / 1024.0),
-                    format!("{}s", process.total_time),
-                ]));
+            // a panicking widget shouldn't take the whole process (and the terminal) down with it
+            if let Err(payload) =
+                panic::catch_unwind(AssertUnwindSafe(|| ui::render(frame, &sys, &state, &network_order)))
+            {
+                state.transient.last_render_error = Some(panic_message(payload.as_ref()));
             }
+        });
 
-            let table = Table::new(
-                rows,
-                [
-                    Constraint::Percentage(20),
-                    Constraint::Percentage(20),
-                    Constraint::Percentage(20),
-                    Constraint::Percentage(20),
-                    Constraint::Percentage(20),
-                ],
-            );
+        if let (Ok(completed), Some(format)) = (&completed, pending_export.take()) {
+            let message = match export::save(completed.buffer, format) {
+                Ok(path) => format!("saved screen to {}", path.display()),
+                Err(e) => format!("couldn't save screen: {}", e),
+            };
 
-            // render the table
-            frame.render_widget(table, bottom_right_inner[1]);
-        });
+            state.set_status(message);
+        }
+
+        // the panic hook writes straight to stderr, so wipe that before the error screen is drawn
+        if !had_error && state.transient.last_render_error.is_some() {
+            terminal.clear()?;
+        }
+    }
+
+    // leave the alternate screen first or anything printed is gone with it
+    drop(_guard);
+
+    if let Some(path) = &session_path {
+        if let Err(e) = session::save(&state.persistent(), path) {
+            eprintln!("pidwatch: {}", e);
+        }
+    }
+
+    if net_errors_exceeded {
+        eprintln!(
+            "pidwatch: {} network errors and drops, more than --alert-net-errors allows",
+            sys.usage.total_network_errors()
+        );
+        std::process::exit(1);
     }
 
-    stdout().execute(LeaveAlternateScreen)?;
-    disable_raw_mode()?;
     Ok(())
 }
@@ -0,0 +1,57 @@
+// switching user is for good, there's no way back to the user pidwatch started as. from
+// then on it only sees what that user could see, so as root every process's details are
+// readable but as anyone else other users' cwd, exe and the like show up as unknown.
+// a setuid root binary lets any local user in, so the switch is limited to root itself
+// or to going back to the real user
+
+#[cfg(unix)]
+pub fn switch_user(name: &str) -> Result<(), String> {
+    use std::{ffi::CString, io};
+
+    let c_name = CString::new(name).map_err(|_| format!("invalid user name '{}'", name))?;
+
+    // getpwnam isn't thread safe, but nothing else looks users up while pidwatch starts
+    let entry = unsafe { libc::getpwnam(c_name.as_ptr()) };
+
+    if entry.is_null() {
+        return Err(format!("no such user '{}'", name));
+    }
+
+    let (uid, gid) = unsafe { ((*entry).pw_uid, (*entry).pw_gid) };
+    let real_uid = unsafe { libc::getuid() };
+
+    if real_uid != 0 && uid != real_uid {
+        return Err(format!(
+            "only root can run pidwatch as '{}', try again with sudo",
+            name
+        ));
+    }
+
+    // the groups go first, once the uid has changed there's no permission left to do it,
+    // and they're the user's supplementary groups as well as its primary one
+    let switched = unsafe {
+        libc::initgroups(c_name.as_ptr(), gid as _) == 0
+            && libc::setgid(gid) == 0
+            && libc::setuid(uid) == 0
+    };
+
+    if switched {
+        return Ok(());
+    }
+
+    let error = io::Error::last_os_error();
+
+    Err(if error.kind() == io::ErrorKind::PermissionDenied {
+        format!(
+            "not allowed to switch to '{}', pidwatch has to be started as root or be setuid root",
+            name
+        )
+    } else {
+        format!("couldn't switch to '{}': {}", name, error)
+    })
+}
+
+#[cfg(not(unix))]
+pub fn switch_user(_name: &str) -> Result<(), String> {
+    Err("switching user is only supported on unix".to_string())
+}
@@ -0,0 +1,276 @@
+use std::path::PathBuf;
+
+#[cfg(target_os = "linux")]
+use std::{collections::HashSet, fs, path::Path};
+
+// what the detail view shows beyond ProcessData, read from /proc/<pid> on linux. anything
+// that couldn't be read, on other platforms or for another user's process, is None
+#[derive(Debug, Clone, Default)]
+pub struct ProcessExtras {
+    pub threads: Option<u64>,
+    pub swap: Option<u64>,
+    // lines in /proc/<pid>/maps, and how many distinct files they map
+    pub memory_maps: Option<usize>,
+    pub mapped_files: Option<usize>,
+    pub oom_score: Option<i32>,
+    // bytes that actually reached the storage layer, not reads served from the cache
+    pub read_bytes: Option<u64>,
+    pub write_bytes: Option<u64>,
+    pub open_fds: Option<usize>,
+    pub environment: Option<Vec<(String, String)>>,
+    // the syscall it's blocked in, or "running", and the kernel function it's waiting in
+    pub syscall: Option<String>,
+    pub wait_channel: Option<String>,
+    pub limits: ResourceLimits,
+    pub io_priority: Option<IoPriority>,
+}
+
+// the io scheduling class, as ionice(1) calls them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoClass {
+    // never set, best-effort at a level that follows the nice value
+    None,
+    Realtime,
+    BestEffort,
+    Idle,
+}
+
+impl IoClass {
+    pub const ALL: [IoClass; 4] = [
+        IoClass::None,
+        IoClass::Realtime,
+        IoClass::BestEffort,
+        IoClass::Idle,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            IoClass::None => "none",
+            IoClass::Realtime => "realtime",
+            IoClass::BestEffort => "best-effort",
+            IoClass::Idle => "idle",
+        }
+    }
+
+    // idle and none take no level of their own
+    pub fn has_level(self) -> bool {
+        matches!(self, IoClass::Realtime | IoClass::BestEffort)
+    }
+
+    pub fn step(self, forward: bool) -> IoClass {
+        let count = IoClass::ALL.len();
+        let index = IoClass::ALL.iter().position(|c| *c == self).unwrap_or_default();
+
+        IoClass::ALL[if forward {
+            (index + 1) % count
+        } else {
+            (index + count - 1) % count
+        }]
+    }
+}
+
+// levels go from 0, the most favoured, to 7
+pub const IO_LEVEL_MAX: u8 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IoPriority {
+    pub class: IoClass,
+    pub level: u8,
+}
+
+impl IoPriority {
+    // what an unset priority behaves like, nice -20 to 19 spread over the eight levels
+    pub fn from_nice(nice: i32) -> IoPriority {
+        IoPriority {
+            class: IoClass::BestEffort,
+            level: ((nice + 20) / 5).clamp(0, IO_LEVEL_MAX as i32) as u8,
+        }
+    }
+
+    // "best-effort 4", or just the class where the level means nothing
+    pub fn label(self) -> String {
+        if self.class.has_level() {
+            format!("{} {}", self.class.label(), self.level)
+        } else {
+            self.class.label().to_string()
+        }
+    }
+}
+
+// (soft, hard) from /proc/<pid>/limits, u64::MAX where it's unlimited, as with
+// RLIM_INFINITY
+#[derive(Debug, Clone, Default)]
+pub struct ResourceLimits {
+    pub open_files: Option<(u64, u64)>,
+    // bytes
+    pub stack_size: Option<(u64, u64)>,
+    pub virtual_memory: Option<(u64, u64)>,
+    // seconds
+    pub cpu_time: Option<(u64, u64)>,
+}
+
+#[cfg(target_os = "linux")]
+pub fn process_extras(pid: u32) -> ProcessExtras {
+    let dir = Path::new("/proc").join(pid.to_string());
+    let read = |name: &str| fs::read_to_string(dir.join(name)).ok();
+
+    // "VmSwap:     1234 kB" and the like
+    let status = read("status").unwrap_or_default();
+    let status_field = |key: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+            .and_then(|value| value.split_whitespace().next()?.parse::<u64>().ok())
+    };
+
+    let io = read("io").unwrap_or_default();
+    let io_field = |key: &str| {
+        io.lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix(": "))
+            .and_then(|value| value.trim().parse::<u64>().ok())
+    };
+
+    let maps = read("maps");
+
+    ProcessExtras {
+        threads: status_field("Threads"),
+        swap: status_field("VmSwap").map(|kb| kb * 1024),
+        memory_maps: maps.as_ref().map(|m| m.lines().count()),
+        // the path is the sixth field, anonymous mappings have none or a [name]
+        mapped_files: maps.as_ref().map(|m| {
+            m.lines()
+                .filter_map(|line| line.split_whitespace().nth(5))
+                .filter(|path| path.starts_with('/'))
+                .collect::<HashSet<&str>>()
+                .len()
+        }),
+        oom_score: read("oom_score").and_then(|s| s.trim().parse().ok()),
+        read_bytes: io_field("read_bytes"),
+        write_bytes: io_field("write_bytes"),
+        open_fds: fs::read_dir(dir.join("fd")).ok().map(|fds| fds.count()),
+        environment: fs::read(dir.join("environ")).ok().map(|environ| {
+            environ
+                .split(|b| *b == 0)
+                .filter(|var| !var.is_empty())
+                .map(|var| {
+                    let var = String::from_utf8_lossy(var);
+
+                    match var.split_once('=') {
+                        Some((key, value)) => (key.to_string(), value.to_string()),
+                        None => (var.to_string(), String::new()),
+                    }
+                })
+                .collect()
+        }),
+        // the syscall number and its arguments, "running" or "-1 ..." outside of one
+        syscall: read("syscall").and_then(|s| s.split_whitespace().next().map(str::to_string)),
+        wait_channel: read("wchan").filter(|w| !w.is_empty() && w != "0"),
+        limits: read("limits").map(|l| parse_limits(&l)).unwrap_or_default(),
+        io_priority: io_priority(pid),
+    }
+}
+
+// the class sits above the 13 bits of level in ioprio_get and ioprio_set's value
+#[cfg(target_os = "linux")]
+const IOPRIO_CLASS_SHIFT: i64 = 13;
+// the `which` for a single process, rather than a group or a user
+#[cfg(target_os = "linux")]
+const IOPRIO_WHO_PROCESS: i64 = 1;
+
+// libc has no wrapper for either syscall
+#[cfg(target_os = "linux")]
+fn io_priority(pid: u32) -> Option<IoPriority> {
+    let value = unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, pid as i64) };
+
+    if value < 0 {
+        return None;
+    }
+
+    let class = match value >> IOPRIO_CLASS_SHIFT {
+        1 => IoClass::Realtime,
+        2 => IoClass::BestEffort,
+        3 => IoClass::Idle,
+        _ => IoClass::None,
+    };
+
+    Some(IoPriority {
+        class,
+        level: (value & ((1 << IOPRIO_CLASS_SHIFT) - 1)) as u8,
+    })
+}
+
+// realtime needs root or CAP_SYS_ADMIN, and changing another user's process needs root too
+#[cfg(target_os = "linux")]
+pub fn set_io_priority(pid: u32, priority: IoPriority) -> Result<(), String> {
+    let class = match priority.class {
+        IoClass::None => 0,
+        IoClass::Realtime => 1,
+        IoClass::BestEffort => 2,
+        IoClass::Idle => 3,
+    };
+    let level = if priority.class.has_level() {
+        priority.level.min(IO_LEVEL_MAX) as i64
+    } else {
+        0
+    };
+    let value = class << IOPRIO_CLASS_SHIFT | level;
+
+    let result = unsafe {
+        libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, pid as i64, value)
+    };
+
+    if result == 0 {
+        return Ok(());
+    }
+
+    match std::io::Error::last_os_error() {
+        e if e.kind() == std::io::ErrorKind::PermissionDenied => Err(format!(
+            "not allowed to set the io priority of {}, it needs root{}",
+            pid,
+            if priority.class == IoClass::Realtime {
+                " for realtime"
+            } else {
+                " for another user's process"
+            }
+        )),
+        e => Err(format!("couldn't set the io priority of {}: {}", pid, e)),
+    }
+}
+
+// "Max open files            1024                 4096                 files"
+#[cfg(target_os = "linux")]
+fn parse_limits(limits: &str) -> ResourceLimits {
+    let limit = |name: &str| {
+        let line = limits.lines().find_map(|line| line.strip_prefix(name))?;
+        let mut values = line.split_whitespace().map(|value| match value {
+            "unlimited" => Some(u64::MAX),
+            _ => value.parse::<u64>().ok(),
+        });
+
+        Some((values.next()??, values.next()??))
+    };
+
+    ResourceLimits {
+        open_files: limit("Max open files"),
+        stack_size: limit("Max stack size"),
+        virtual_memory: limit("Max address space"),
+        cpu_time: limit("Max cpu time"),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn process_extras(_pid: u32) -> ProcessExtras {
+    ProcessExtras::default()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_io_priority(_pid: u32, _priority: IoPriority) -> Result<(), String> {
+    Err("io priorities can only be set on linux".to_string())
+}
+
+// strace on the PATH, for following a process's syscalls from another terminal
+pub fn find_strace() -> Option<PathBuf> {
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join("strace"))
+        .find(|path| path.is_file())
+}
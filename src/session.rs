@@ -0,0 +1,181 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    app::{PerformanceMode, PersistentAppState, StateDisplay},
+    columns::{Column, ColumnLayout},
+    json::{self, Value},
+    labels::{LabelMode, SizeUnit},
+    sort::{Aggregation, DiskSorter, ProcessSorter},
+};
+
+// $XDG_STATE_HOME/pidwatch/state.json (or ~/.local/state/...), written when pidwatch quits
+pub fn default_path() -> Option<PathBuf> {
+    let state_dir = std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+        })?;
+
+    Some(state_dir.join("pidwatch").join("state.json"))
+}
+
+pub fn save(state: &PersistentAppState, path: &Path) -> Result<(), String> {
+    let session = Value::object([
+        ("sorter", state.sorter.label().into()),
+        ("sort_reversed", state.sort_reversed.into()),
+        ("aggregation", state.aggregation.label().into()),
+        ("relative_cpu", state.relative_cpu.into()),
+        ("merge_threads", state.merge_threads.into()),
+        ("show_nice", state.show_nice.into()),
+        ("show_sched_policy", state.show_sched_policy.into()),
+        ("show_growth", state.show_growth.into()),
+        ("show_connection_count", state.show_connection_count.into()),
+        ("state_display", state.state_display.map(StateDisplay::label).into()),
+        ("disk_sorter", state.disk_sorter.label().into()),
+        ("labels", state.labels.label().into()),
+        ("units", state.units.label().into()),
+        ("performance_mode", state.performance_mode.label().into()),
+        (
+            "column_order",
+            state.columns.order.iter().map(|c| c.key()).collect::<Vec<&str>>().join(", ").into(),
+        ),
+        (
+            "column_widths",
+            Value::object(
+                state
+                    .columns
+                    .order
+                    .iter()
+                    .filter(|c| state.columns.widths.contains_key(c))
+                    .map(|c| (c.key(), u32::from(state.columns.width(*c)).into())),
+            ),
+        ),
+    ]);
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("couldn't create {}: {}", dir.display(), e))?;
+    }
+
+    fs::write(path, format!("{}\n", session))
+        .map_err(|e| format!("couldn't write {}: {}", path.display(), e))
+}
+
+// None before the first run, settings missing from the file keep their defaults
+pub fn load(path: &Path) -> Result<Option<PersistentAppState>, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("couldn't read {}: {}", path.display(), e)),
+    };
+    let session = json::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let mut state = PersistentAppState::default();
+    let flag =
+        |key: &str, default: bool| session.get(key).and_then(Value::as_bool).unwrap_or(default);
+    let string = |key: &str| session.get(key).and_then(Value::as_str);
+
+    if let Some(sorter) = string("sorter") {
+        state.sorter =
+            ProcessSorter::parse(sorter).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+
+    if let Some(aggregation) = string("aggregation") {
+        state.aggregation =
+            Aggregation::parse(aggregation).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+
+    if let Some(sorter) = string("disk_sorter") {
+        state.disk_sorter =
+            DiskSorter::parse(sorter).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+
+    if let Some(labels) = string("labels") {
+        state.labels =
+            LabelMode::parse(labels).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+
+    if let Some(units) = string("units") {
+        state.units = SizeUnit::parse(units).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+
+    if let Some(display) = string("state_display") {
+        state.state_display =
+            Some(StateDisplay::parse(display).map_err(|e| format!("{}: {}", path.display(), e))?);
+    }
+
+    if let Some(mode) = string("performance_mode") {
+        state.performance_mode =
+            PerformanceMode::parse(mode).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+
+    if let Some(order) = string("column_order") {
+        state.columns.order =
+            ColumnLayout::parse_order(order).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+
+    if let Some(Value::Object(widths)) = session.get("column_widths") {
+        for (key, width) in widths {
+            let column = Column::parse(key).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+            if let Some(width) = width.as_f64() {
+                state.columns.set_width(column, width as u16);
+            }
+        }
+    }
+
+    state.sort_reversed = flag("sort_reversed", state.sort_reversed);
+    state.relative_cpu = flag("relative_cpu", state.relative_cpu);
+    state.merge_threads = flag("merge_threads", state.merge_threads);
+    state.show_nice = flag("show_nice", state.show_nice);
+    state.show_sched_policy = flag("show_sched_policy", state.show_sched_policy);
+    state.show_growth = flag("show_growth", state.show_growth);
+    state.show_connection_count = flag("show_connection_count", state.show_connection_count);
+
+    Ok(Some(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_saved_session_loads_back_the_same() {
+        let mut state = PersistentAppState {
+            sorter: ProcessSorter::ByName,
+            sort_reversed: true,
+            aggregation: Aggregation::Max,
+            relative_cpu: true,
+            merge_threads: false,
+            show_nice: true,
+            show_connection_count: true,
+            state_display: Some(StateDisplay::Word),
+            disk_sorter: DiskSorter::ByFree,
+            labels: LabelMode::Compact,
+            units: SizeUnit::Gib,
+            performance_mode: PerformanceMode::Low,
+            ..PersistentAppState::default()
+        };
+        state.columns.move_column(Column::Name, false, &Column::ALL);
+        state.columns.resize(Column::Name, true);
+
+        let path = std::env::temp_dir()
+            .join(format!("pidwatch-session-{}", std::process::id()))
+            .join("state.json");
+
+        save(&state, &path).unwrap();
+        let loaded = load(&path);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(loaded, Ok(Some(state)));
+    }
+
+    #[test]
+    fn no_session_file_is_not_an_error() {
+        let path = std::env::temp_dir().join("pidwatch-no-session").join("state.json");
+
+        assert_eq!(load(&path), Ok(None));
+    }
+}
@@ -0,0 +1,491 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    iter::Peekable,
+    str::Chars,
+};
+
+use itertools::Itertools;
+
+use crate::{
+    columns::Column,
+    info::{Disk, ProcessData},
+};
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessSorter {
+    ByCpu,
+    ByMemory,
+    ByUptime,
+    ByPid,
+    ByName,
+    ByNice,
+    BySteal,
+    ByGrowth,
+    ByFaults,
+}
+
+impl ProcessSorter {
+    // the order `s` cycles through
+    pub fn next(self) -> ProcessSorter {
+        match self {
+            ProcessSorter::ByCpu => ProcessSorter::ByMemory,
+            ProcessSorter::ByMemory => ProcessSorter::ByUptime,
+            ProcessSorter::ByUptime => ProcessSorter::ByPid,
+            ProcessSorter::ByPid => ProcessSorter::ByName,
+            ProcessSorter::ByName => ProcessSorter::ByNice,
+            ProcessSorter::ByNice => ProcessSorter::BySteal,
+            ProcessSorter::BySteal => ProcessSorter::ByGrowth,
+            ProcessSorter::ByGrowth => ProcessSorter::ByFaults,
+            ProcessSorter::ByFaults => ProcessSorter::ByCpu,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ProcessSorter::ByCpu => "cpu",
+            ProcessSorter::ByMemory => "memory",
+            ProcessSorter::ByUptime => "uptime",
+            ProcessSorter::ByPid => "pid",
+            ProcessSorter::ByName => "name",
+            ProcessSorter::ByNice => "nice",
+            ProcessSorter::BySteal => "steal",
+            ProcessSorter::ByGrowth => "growth",
+            ProcessSorter::ByFaults => "faults",
+        }
+    }
+
+    // the process table column it sorts by
+    pub fn column(self) -> Column {
+        match self {
+            ProcessSorter::ByCpu => Column::Cpu,
+            ProcessSorter::ByMemory => Column::Memory,
+            ProcessSorter::ByUptime => Column::Uptime,
+            ProcessSorter::ByPid => Column::Pid,
+            ProcessSorter::ByName => Column::Name,
+            ProcessSorter::ByNice => Column::Nice,
+            ProcessSorter::BySteal => Column::Steal,
+            ProcessSorter::ByGrowth => Column::Growth,
+            ProcessSorter::ByFaults => Column::Faults,
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<ProcessSorter, String> {
+        match value {
+            "cpu" => Ok(ProcessSorter::ByCpu),
+            "memory" => Ok(ProcessSorter::ByMemory),
+            "uptime" => Ok(ProcessSorter::ByUptime),
+            "pid" => Ok(ProcessSorter::ByPid),
+            "name" => Ok(ProcessSorter::ByName),
+            "nice" => Ok(ProcessSorter::ByNice),
+            "steal" => Ok(ProcessSorter::BySteal),
+            "growth" => Ok(ProcessSorter::ByGrowth),
+            "faults" => Ok(ProcessSorter::ByFaults),
+            _ => Err(format!(
+                "unknown column '{}', expected cpu, memory, uptime, pid, name, nice, steal, growth \
+                 or faults",
+                value
+            )),
+        }
+    }
+
+    // which way compare orders the column, for the arrow in the header
+    pub fn descending(self) -> bool {
+        matches!(
+            self,
+            ProcessSorter::ByCpu
+                | ProcessSorter::ByMemory
+                | ProcessSorter::ByUptime
+                | ProcessSorter::BySteal
+                | ProcessSorter::ByGrowth
+                | ProcessSorter::ByFaults
+        )
+    }
+
+    // usage columns put the biggest first, identifiers sort ascending and
+    // nice puts the highest priority (most negative) first
+    pub fn compare(self, a: &ProcessData, b: &ProcessData) -> Ordering {
+        match self {
+            ProcessSorter::ByCpu => b.cpu_usage.total_cmp(&a.cpu_usage),
+            ProcessSorter::ByMemory => b.ram.cmp(&a.ram),
+            ProcessSorter::ByUptime => b.total_time.total_cmp(&a.total_time),
+            ProcessSorter::ByPid => a.pid.cmp(&b.pid),
+            ProcessSorter::ByName => natural_cmp(&a.name, &b.name),
+            ProcessSorter::ByNice => a.nice.cmp(&b.nice),
+            // unknown steal sorts after every known figure
+            ProcessSorter::BySteal => b.cpu_steal_ms.cmp(&a.cpu_steal_ms),
+            // so does growth that hasn't been measured yet
+            ProcessSorter::ByGrowth => {
+                let growth = |p: &ProcessData| p.memory_growth.unwrap_or(f32::NEG_INFINITY);
+                growth(b).total_cmp(&growth(a))
+            }
+            ProcessSorter::ByFaults => {
+                let rate = |p: &ProcessData| p.major_fault_rate.unwrap_or(f32::NEG_INFINITY);
+                rate(b).total_cmp(&rate(a))
+            }
+        }
+    }
+}
+
+// runs of digits compare by their value, so worker2 comes before worker10
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a_chars, mut b_chars) = (a.chars().peekable(), b.chars().peekable());
+
+    loop {
+        match (a_chars.peek(), b_chars.peek()) {
+            (None, None) => break,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let digits = |chars: &mut Peekable<Chars>| {
+                    let mut run = String::new();
+
+                    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                        run.push(c);
+                    }
+
+                    run
+                };
+
+                let (x, y) = (digits(&mut a_chars), digits(&mut b_chars));
+                let (x_value, y_value) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+
+                // longer without leading zeros is bigger, then digit by digit
+                let order = x_value
+                    .len()
+                    .cmp(&y_value.len())
+                    .then_with(|| x_value.cmp(y_value));
+
+                if order != Ordering::Equal {
+                    return order;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let order = x.cmp(y);
+
+                if order != Ordering::Equal {
+                    return order;
+                }
+
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+
+    // equal apart from leading zeros, fall back on the plain order so it's still total
+    a.cmp(b)
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskSorter {
+    ByUsagePct,
+    ByFree,
+    ByName,
+    ByTotal,
+    ByReadRate,
+    ByWriteRate,
+}
+
+impl DiskSorter {
+    pub const ALL: [DiskSorter; 6] = [
+        DiskSorter::ByUsagePct,
+        DiskSorter::ByFree,
+        DiskSorter::ByName,
+        DiskSorter::ByTotal,
+        DiskSorter::ByReadRate,
+        DiskSorter::ByWriteRate,
+    ];
+
+    pub fn parse(value: &str) -> Result<DiskSorter, String> {
+        DiskSorter::ALL
+            .into_iter()
+            .find(|s| s.label() == value)
+            .ok_or_else(|| format!("unknown disk sort '{}'", value))
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DiskSorter::ByUsagePct => "usage",
+            DiskSorter::ByFree => "free",
+            DiskSorter::ByName => "mount",
+            DiskSorter::ByTotal => "size",
+            DiskSorter::ByReadRate => "reads",
+            DiskSorter::ByWriteRate => "writes",
+        }
+    }
+
+    // fullest, biggest and busiest first, but the least free space and names go up
+    pub fn descending(self) -> bool {
+        !matches!(self, DiskSorter::ByFree | DiskSorter::ByName)
+    }
+
+    pub fn compare(self, a: &Disk, b: &Disk) -> Ordering {
+        match self {
+            DiskSorter::ByUsagePct => b.percent.total_cmp(&a.percent),
+            DiskSorter::ByFree => a.free.cmp(&b.free),
+            DiskSorter::ByName => a.mount.cmp(&b.mount),
+            DiskSorter::ByTotal => b.total.cmp(&a.total),
+            DiskSorter::ByReadRate => b.read_per_sec.cmp(&a.read_per_sec),
+            DiskSorter::ByWriteRate => b.write_per_sec.cmp(&a.write_per_sec),
+        }
+    }
+}
+
+// stable, so disks that compare equal stay in the order they were found in
+pub fn sort_disks(disks: &mut [&Disk], sorter: DiskSorter) {
+    disks.sort_by(|a, b| sorter.compare(a, b));
+}
+
+// drops thread entries whose owning process is listed, its cpu and memory already cover them
+pub fn merge_threads(processes: &[ProcessData]) -> Vec<&ProcessData> {
+    let pids = processes.iter().map(|p| p.pid).collect::<HashSet<u32>>();
+
+    processes
+        .iter()
+        .filter(|p| p.thread_of.is_none_or(|owner| !pids.contains(&owner)))
+        .collect()
+}
+
+// how a group's cpu and memory are combined, the whole footprint or the worst member
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    Sum,
+    Max,
+}
+
+impl Aggregation {
+    pub fn toggle(self) -> Aggregation {
+        match self {
+            Aggregation::Sum => Aggregation::Max,
+            Aggregation::Max => Aggregation::Sum,
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Aggregation, String> {
+        match value {
+            "sum" => Ok(Aggregation::Sum),
+            "max" => Ok(Aggregation::Max),
+            _ => Err(format!("unknown aggregation '{}', expected sum or max", value)),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Aggregation::Sum => "sum",
+            Aggregation::Max => "max",
+        }
+    }
+}
+
+// a row of the process table, every process sharing a name folded into one
+#[derive(Debug, Clone)]
+pub struct ProcessGroup {
+    // the busiest member's details with the others' figures added in, its pid only stands
+    // for the whole group when there's just the one member
+    pub process: ProcessData,
+    // every member, busiest first
+    pub pids: Vec<u32>,
+}
+
+impl ProcessGroup {
+    // the pid to act on, None when the row stands for several processes
+    pub fn pid(&self) -> Option<u32> {
+        if self.pids.len() == 1 {
+            self.pids.first().copied()
+        } else {
+            None
+        }
+    }
+}
+
+// the rows of the process table, processes sharing a name are folded into the busiest one,
+// which is the only one copied
+pub fn grouped_processes(
+    processes: &[&ProcessData],
+    sorter: ProcessSorter,
+    aggregation: Aggregation,
+) -> Vec<ProcessGroup> {
+    let sorted_by_cpu = processes
+        .iter()
+        .copied()
+        .sorted_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+
+    // now sum any processes with the same name together, the index keeps thousands of
+    // same-named workers from turning this quadratic
+    let mut groups: Vec<ProcessGroup> = vec![];
+    let mut index: HashMap<&str, usize> = HashMap::new();
+
+    for process in sorted_by_cpu {
+        if let Some(&i) = index.get(process.name.as_str()) {
+            let group = &mut groups[i];
+            let existing = &mut group.process;
+            group.pids.push(process.pid);
+
+            if aggregation == Aggregation::Sum {
+                existing.cpu_usage += process.cpu_usage;
+                existing.ram += process.ram;
+            } else {
+                // sorted by cpu, so the first of the group is already the hottest
+                existing.ram = existing.ram.max(process.ram);
+            }
+
+            existing.total_time += process.total_time;
+            existing.cpu_steal_ms = match (existing.cpu_steal_ms, process.cpu_steal_ms) {
+                (Some(a), Some(b)) => Some(a + b),
+                (a, b) => a.or(b),
+            };
+            existing.memory_growth = match (existing.memory_growth, process.memory_growth) {
+                (Some(a), Some(b)) => Some(a + b),
+                (a, b) => a.or(b),
+            };
+            existing.major_fault_rate =
+                match (existing.major_fault_rate, process.major_fault_rate) {
+                    (Some(a), Some(b)) => Some(a + b),
+                    (a, b) => a.or(b),
+                };
+        } else {
+            index.insert(&process.name, groups.len());
+            groups.push(ProcessGroup {
+                process: process.clone(),
+                pids: vec![process.pid],
+            });
+        }
+    }
+
+    groups.sort_by(|a, b| sorter.compare(&a.process, &b.process));
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn natural_cmp_orders_numbered_names_by_value() {
+        let mut names = vec!["worker10", "worker2", "worker1"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+
+        assert_eq!(names, ["worker1", "worker2", "worker10"]);
+        assert_eq!(natural_cmp("worker2", "worker10"), Ordering::Less);
+        assert_eq!(natural_cmp("worker10", "worker2"), Ordering::Greater);
+    }
+
+    fn process(pid: u32, name: &str, cpu_usage: f32) -> ProcessData {
+        ProcessData {
+            cpu_usage,
+            ..ProcessData::named(pid, name)
+        }
+    }
+
+    #[test]
+    fn a_grouped_row_holds_every_member_pid() {
+        let processes = [
+            process(10, "worker", 1.0),
+            process(11, "shell", 2.0),
+            process(12, "worker", 5.0),
+            process(13, "worker", 3.0),
+        ];
+        let processes = processes.iter().collect::<Vec<&ProcessData>>();
+
+        let groups = grouped_processes(&processes, ProcessSorter::ByPid, Aggregation::Sum);
+        let worker = groups.iter().find(|g| g.process.name == "worker").unwrap();
+        let shell = groups.iter().find(|g| g.process.name == "shell").unwrap();
+
+        // busiest first
+        assert_eq!(worker.pids, [12, 13, 10]);
+        assert_eq!(worker.pid(), None);
+        assert_eq!(worker.process.cpu_usage, 9.0);
+        assert_eq!(shell.pids, [11]);
+        assert_eq!(shell.pid(), Some(11));
+    }
+
+    #[test]
+    fn by_nice_puts_the_highest_priority_first() {
+        let nice = |pid: u32, nice: i32| ProcessData {
+            nice,
+            ..ProcessData::named(pid, "worker")
+        };
+        let mut processes = [nice(1, 0), nice(2, 19), nice(3, -20), nice(4, 5), nice(5, -5)];
+
+        processes.sort_by(|a, b| ProcessSorter::ByNice.compare(a, b));
+
+        let order = processes.iter().map(|p| p.nice).collect::<Vec<i32>>();
+        assert_eq!(order, [-20, -5, 0, 5, 19]);
+        assert!(!ProcessSorter::ByNice.descending());
+    }
+
+    #[test]
+    fn by_nice_keeps_equal_values_in_place() {
+        let processes = [ProcessData::named(7, "a"), ProcessData::named(3, "b")];
+        let mut sorted = processes.iter().collect::<Vec<&ProcessData>>();
+
+        sorted.sort_by(|a, b| ProcessSorter::ByNice.compare(a, b));
+
+        assert_eq!(sorted.iter().map(|p| p.pid).collect::<Vec<u32>>(), [7, 3]);
+    }
+
+    #[test]
+    fn merge_threads_drops_threads_of_listed_processes() {
+        let thread = |pid: u32, owner: u32| ProcessData {
+            thread_of: Some(owner),
+            ..ProcessData::named(pid, "firefox")
+        };
+        let processes = [
+            ProcessData::named(100, "firefox"),
+            thread(101, 100),
+            thread(102, 100),
+            // its process isn't in the list, so it's the only trace of it
+            thread(201, 200),
+            ProcessData::named(300, "bash"),
+        ];
+
+        let merged = merge_threads(&processes)
+            .iter()
+            .map(|p| p.pid)
+            .collect::<Vec<u32>>();
+
+        assert_eq!(merged, [100, 201, 300]);
+    }
+
+    #[test]
+    fn each_group_stands_for_its_busiest_member() {
+        let named = |pid: u32, name: &str, cpu_usage: f32, ram: u64| ProcessData {
+            ram,
+            ..process(pid, name, cpu_usage)
+        };
+        let processes = [
+            named(1, "worker", 2.0, 100),
+            named(2, "shell", 1.0, 10),
+            named(3, "worker", 7.0, 300),
+            named(4, "shell", 4.0, 20),
+            named(5, "worker", 4.0, 200),
+        ];
+        let processes = processes.iter().collect::<Vec<&ProcessData>>();
+
+        let groups = grouped_processes(&processes, ProcessSorter::ByCpu, Aggregation::Max);
+
+        // the first of each name in cpu order keeps its details, as before the index
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].process.pid, 3);
+        assert_eq!(groups[0].process.cpu_usage, 7.0);
+        assert_eq!(groups[0].process.ram, 300);
+        assert_eq!(groups[0].pids, [3, 5, 1]);
+        assert_eq!(groups[1].process.pid, 4);
+        assert_eq!(groups[1].pids, [4, 2]);
+    }
+
+    #[test]
+    fn grouping_survives_a_nan_cpu_usage() {
+        let processes = [process(1, "a", f32::NAN), process(2, "a", 3.0), process(3, "b", 1.0)];
+        let processes = processes.iter().collect::<Vec<&ProcessData>>();
+
+        let groups = grouped_processes(&processes, ProcessSorter::ByCpu, Aggregation::Sum);
+
+        assert_eq!(groups.len(), 2);
+        assert!(groups.iter().any(|g| g.pids.len() == 2 && g.pids.contains(&1)));
+    }
+}
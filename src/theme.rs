@@ -0,0 +1,150 @@
+use ratatui::style::Color;
+
+// how long the terminal gets to answer before pidwatch assumes a dark background
+#[cfg(unix)]
+const QUERY_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(200);
+
+// picked from the terminal's background at startup unless the config says otherwise
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Dark,
+    Light,
+}
+
+impl Theme {
+    // None for auto
+    pub fn parse(value: &str) -> Result<Option<Theme>, String> {
+        match value {
+            "auto" => Ok(None),
+            "dark" => Ok(Some(Theme::Dark)),
+            "light" => Ok(Some(Theme::Light)),
+            _ => Err(format!(
+                "unknown theme '{}', expected auto, dark or light",
+                value
+            )),
+        }
+    }
+
+    // must run in raw mode and before crossterm starts reading input, or the reply
+    // ends up echoed or parsed as key presses
+    pub fn detect() -> Theme {
+        query_background()
+            .map(|(r, g, b)| {
+                if 0.2126 * r + 0.7152 * g + 0.0722 * b > 0.5 {
+                    Theme::Light
+                } else {
+                    Theme::Dark
+                }
+            })
+            .unwrap_or(Theme::Dark)
+    }
+
+    // the background of every other row in the process table
+    pub fn stripe(self) -> Color {
+        match self {
+            Theme::Dark => Color::Indexed(236),
+            Theme::Light => Color::Indexed(254),
+        }
+    }
+
+    // stands out the most against the background, for things that blink
+    pub fn contrast(self) -> Color {
+        match self {
+            Theme::Dark => Color::White,
+            Theme::Light => Color::Black,
+        }
+    }
+
+    // yellow is unreadable on white
+    pub fn highlight(self) -> Color {
+        match self {
+            Theme::Dark => Color::Yellow,
+            Theme::Light => Color::Blue,
+        }
+    }
+}
+
+// asks with OSC 11 and follows it with a device attributes query, which every terminal
+// answers, so a terminal without OSC 11 support still ends the read rather than leaving
+// it to swallow the first key press. read here with poll rather than on a thread, which
+// would outlive a timeout and race crossterm for input
+#[cfg(unix)]
+fn query_background() -> Option<(f32, f32, f32)> {
+    use std::{
+        fs::File,
+        io::{Read, Write},
+        os::fd::AsRawFd,
+        time::Instant,
+    };
+
+    let mut tty = File::options()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+
+    tty.write_all(b"\x1b]11;?\x07\x1b[c").ok()?;
+    tty.flush().ok()?;
+
+    let deadline = Instant::now() + QUERY_TIMEOUT;
+    let mut reply = vec![];
+    let mut buffer = [0u8; 256];
+
+    // the device attributes reply is "\x1b[?...c"
+    let answered = |reply: &[u8]| {
+        String::from_utf8_lossy(reply)
+            .rsplit_once("\x1b[?")
+            .is_some_and(|(_, rest)| rest.ends_with('c'))
+    };
+
+    // once past the deadline whatever has already arrived is still drained, so a late
+    // reply doesn't turn up as key presses
+    while !answered(&reply) {
+        let left = deadline.saturating_duration_since(Instant::now());
+        let mut fd = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        if unsafe { libc::poll(&mut fd, 1, left.as_millis() as libc::c_int) } <= 0 {
+            break;
+        }
+
+        match tty.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => reply.extend_from_slice(&buffer[..n]),
+        }
+    }
+
+    if !answered(&reply) {
+        return None;
+    }
+
+    parse_reply(&String::from_utf8_lossy(&reply))
+}
+
+#[cfg(not(unix))]
+fn query_background() -> Option<(f32, f32, f32)> {
+    None
+}
+
+// "\x1b]11;rgb:ffff/ffff/ffff\x07", with 1 to 4 hex digits a channel, or ST for the bell
+#[cfg(unix)]
+fn parse_reply(reply: &str) -> Option<(f32, f32, f32)> {
+    let (_, rgb) = reply.split_once("]11;rgb:")?;
+    let rgb = rgb.split(['\x07', '\x1b']).next()?;
+
+    let channels = rgb
+        .split('/')
+        .map(|c| {
+            let max = 16u32.checked_pow(c.len() as u32)? - 1;
+            Some(u32::from_str_radix(c, 16).ok()? as f32 / max as f32)
+        })
+        .collect::<Option<Vec<f32>>>()?;
+
+    match channels[..] {
+        [r, g, b] => Some((r, g, b)),
+        _ => None,
+    }
+}
@@ -0,0 +1,1660 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    prelude::{Frame, Line, Span, Stylize},
+    style::{Color, Style},
+    widgets::{
+        block::Title, BarChart, Block, BorderType, Borders, Cell, Clear, Paragraph, Row, Table,
+        TableState, Wrap,
+    },
+};
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    app::{AppState, Panel, StateDisplay},
+    columns::Column,
+    history::RingBuffer,
+    info::{self, AddressScope, CoreSummary, MemoryPressure, ProcessState, Subsystem, UserUsage},
+    labels::{ClockStat, Label, LabelMode, SizeUnit},
+    sort::{self, ProcessGroup},
+    util,
+};
+
+mod detail;
+mod scrollbar;
+mod widgets;
+
+// in place of cpu figures until there are two samples to measure between
+const MEASURING: &str = "measuring…";
+// below this the panels get squeezed down to nothing
+pub const MIN_WIDTH: u16 = 60;
+pub const MIN_HEIGHT: u16 = 20;
+// memory climbing faster than this is worth noticing
+const LEAK_WARN_BYTES_PER_MIN: f32 = 1024.0 * 1024.0;
+// major faults per second above this look like a process thrashing swap
+const THRASH_FAULTS_PER_SEC: f32 = 100.0;
+// steal above this is worth noticing
+const STEAL_WARN_PERCENT: f32 = 5.0;
+// the health score is green from here up, yellow from HEALTH_POOR and red below that
+const HEALTH_GOOD: f32 = 70.0;
+const HEALTH_POOR: f32 = 40.0;
+
+fn pressure_color(pressure: MemoryPressure) -> Color {
+    match pressure {
+        MemoryPressure::Normal => Color::Blue,
+        MemoryPressure::Elevated => Color::Yellow,
+        MemoryPressure::High => Color::LightRed,
+        MemoryPressure::Critical => Color::Red,
+    }
+}
+
+// the focused panel gets a heavier border
+fn border_type(state: &AppState, panel: Panel) -> BorderType {
+    if state.focused == panel {
+        BorderType::Thick
+    } else {
+        BorderType::Plain
+    }
+}
+
+// toggles twice a second, for anything that should flash
+fn blink_on() -> bool {
+    (SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+        / 500)
+        .is_multiple_of(2)
+}
+
+// one line with the current rate and the peak of the sparkline drawn next to it
+fn render_rate(
+    frame: &mut Frame,
+    state: &AppState,
+    area: Rect,
+    arrow: &str,
+    rate: u64,
+    history: Option<&RingBuffer<u64>>,
+    color: Color,
+) {
+    let data = history
+        .map(|h| h.iter().copied().collect::<Vec<u64>>())
+        .unwrap_or_default();
+    let peak = data.iter().copied().max().unwrap_or(0);
+    let labels = state.labels;
+
+    let label = format!(
+        "{} {}/s ({}/s)",
+        arrow,
+        labels.bytes(state.units, rate),
+        labels.field(Label::Peak, labels.bytes(state.units, peak))
+    );
+
+    // scaled to the window's own peak so quiet interfaces still show their shape
+    frame.render_widget(
+        widgets::SparklineWidget {
+            label: &label,
+            data: &data,
+            max: 0,
+            label_width: 36,
+            color,
+        },
+        area,
+    );
+}
+
+// how long a panel's marker stays lit after its data is
+// refreshed, long enough to be drawn at least once in PerformanceMode::Low
+const REFRESH_PULSE: Duration = Duration::from_millis(600);
+// past this the marker also says how long ago, for the panels on slow timers
+const REFRESH_AGE_SHOWN: Duration = Duration::from_secs(5);
+
+// a dot in the panel's title that lights up whenever its part of the data is refreshed
+fn refresh_marker(state: &AppState, subsystem: Subsystem) -> Title<'static> {
+    let marker = match state.refreshed_at.get(&subsystem).map(Instant::elapsed) {
+        Some(age) if age < REFRESH_PULSE => "●".bold(),
+        Some(age) if age >= REFRESH_AGE_SHOWN => {
+            format!("· {} ago", util::format_duration_short(age.as_secs())).dim()
+        }
+        _ => "·".dim(),
+    };
+
+    Title::from(marker).alignment(Alignment::Right)
+}
+
+// "System Health: 78" on the right of the top line
+fn health_title(sys: &info::SystemInfo) -> Title<'static> {
+    // idle until the cpu has been measured, which would flatter it
+    let title = if !sys.usage.cpu_measured {
+        format!("System Health: {}", MEASURING).dim()
+    } else {
+        let score = sys.health_score();
+        let text = format!("System Health: {:.0}", score);
+
+        if score >= HEALTH_GOOD {
+            text.green()
+        } else if score >= HEALTH_POOR {
+            text.yellow()
+        } else {
+            text.red()
+        }
+    };
+
+    Title::from(title).alignment(Alignment::Right)
+}
+
+// the bottom line, pidwatch's own state on the left and the latest numbers on the right
+fn status_bar<'a>(
+    sys: &info::SystemInfo,
+    state: &'a AppState,
+    rows: &[ProcessGroup],
+) -> Block<'a> {
+    let mut status_bar = Block::new()
+        .borders(Borders::TOP)
+        .title("pidwatch")
+        .title(format!(
+            "up {}",
+            util::format_duration_short(sys.spec.uptime.parse().unwrap_or_default())
+        ))
+        .title(format!("perf: {} (1/2/3)", state.performance_mode.label()))
+        .bold();
+
+    if state.labels == LabelMode::Compact {
+        status_bar = status_bar.title("compact (v)");
+    }
+
+    status_bar = status_bar.title(format!("units: {} (u)", state.units.label()));
+
+    if let Some(pid) = state.watched_pid {
+        let watched = match sys.processes.iter().find(|p| p.pid == pid) {
+            Some(process) => format!(
+                "watching {} ({}): {:.2}% cpu, {:.2} MB",
+                process.name,
+                pid,
+                process.cpu_usage,
+                process.ram as f32 / 1024.0 / 1024.0
+            ),
+            None => format!("watched process {} has exited", pid),
+        };
+
+        status_bar = status_bar.title(watched.cyan());
+    }
+
+    if state.column_edit.is_some() {
+        status_bar = status_bar.title(
+            "columns: ←/→ pick, +/- width, </> move, enter save, esc cancel"
+                .fg(state.theme.highlight()),
+        );
+    }
+
+    if let Some(edit) = &state.note_edit {
+        status_bar = status_bar.title(
+            format!("note for {}: {}_ (enter save, esc cancel)", edit.pid, edit.text)
+                .fg(state.theme.highlight()),
+        );
+    }
+
+    if let Some(search) = &state.search {
+        let cursor = if search.typing { "_" } else { " (n/N)" };
+
+        // a typo would otherwise just leave nothing highlighted
+        status_bar = status_bar.title(
+            if !search.query.is_empty() && state.search_matches(rows).is_empty() {
+                format!(
+                    "search: '{}' matched 0 of {} rows (esc to clear)",
+                    search.query,
+                    rows.len()
+                )
+                .red()
+            } else {
+                format!("search: {}{}", search.query, cursor).fg(state.theme.highlight())
+            },
+        );
+    }
+
+    if let Some(message) = state.status_message() {
+        status_bar = status_bar.title(Title::from(message).alignment(Alignment::Right));
+    }
+
+    if let Some(alert) = &state.paused_by {
+        let alert = format!("paused by alert: {} (space to resume)", alert);
+
+        status_bar = status_bar.title(if blink_on() {
+            alert.black().on_red()
+        } else {
+            alert.red()
+        });
+    } else if state.paused {
+        status_bar = status_bar.title("paused (space)".fg(state.theme.highlight()));
+    }
+
+    // flaky hardware or a bad cable, red while the count is still climbing
+    let network_errors = sys.usage.total_network_errors();
+
+    if network_errors > 0 {
+        let indicator = if state.network_error_rate > 0.0 {
+            format!("[NET ERRORS: {} ↑]", network_errors).red()
+        } else {
+            format!("[NET ERRORS: {}]", network_errors).yellow()
+        };
+
+        status_bar = status_bar.title(Title::from(indicator).alignment(Alignment::Right));
+    }
+
+    let throttled = state.clock_history.throttled_cores();
+
+    if !throttled.is_empty() {
+        let cores = throttled
+            .iter()
+            .map(|(core, _, _)| core.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        status_bar = status_bar.title(
+            Title::from(format!("⚠ thermal throttling detected (CPU {})", cores).red())
+                .alignment(Alignment::Right),
+        );
+    }
+
+    if let Some(took) = state.slow_refresh {
+        status_bar = status_bar.title(
+            Title::from(format!("refresh slow ({}ms)", took.as_millis()).yellow())
+                .alignment(Alignment::Right),
+        );
+    }
+
+    // "R:3 S:142 D:0 Z:0 T:0 I:12", zombies and stopped processes stand out when there are any
+    if !state.process_state_summary.is_empty() {
+        let mut spans = vec![];
+
+        for process_state in [
+            ProcessState::Running,
+            ProcessState::Sleeping,
+            ProcessState::DiskSleep,
+            ProcessState::Zombie,
+            ProcessState::Stopped,
+            ProcessState::Idle,
+            ProcessState::Unknown,
+        ] {
+            let n = state
+                .process_state_summary
+                .get(&process_state)
+                .copied()
+                .unwrap_or(0);
+
+            // an unknown state only comes up on platforms sysinfo can't map
+            if process_state == ProcessState::Unknown && n == 0 {
+                continue;
+            }
+
+            let span = Span::from(format!("{}:{}", process_state.code(), n));
+
+            if !spans.is_empty() {
+                spans.push(Span::from(" "));
+            }
+
+            spans.push(match process_state {
+                ProcessState::Zombie if n > 0 => span.red().bold(),
+                ProcessState::Stopped if n > 0 => span.yellow().bold(),
+                _ => span,
+            });
+        }
+
+        status_bar = status_bar.title(Title::from(Line::from(spans)).alignment(Alignment::Right));
+    }
+
+    // always visible, whatever the panels are showing
+    let cpu_usage = sys.usage.average_cpu_usage();
+    let cpu_color = if !sys.usage.cpu_measured {
+        Color::Gray
+    } else if cpu_usage < 50.0 {
+        Color::Green
+    } else if cpu_usage < 80.0 {
+        Color::Yellow
+    } else {
+        Color::Red
+    };
+
+    status_bar = status_bar.title(
+        Title::from(Line::from(vec![
+            if sys.usage.cpu_measured {
+                format!(" CPU {:.0}% ", cpu_usage)
+            } else {
+                format!(" CPU {} ", MEASURING)
+            }
+            .black()
+            .bg(cpu_color),
+            " ".into(),
+            format!(" MEM {:.0}% ", sys.usage.memory_percent())
+                .black()
+                .bg(pressure_color(sys.memory_pressure_level())),
+        ]))
+        .alignment(Alignment::Right),
+    );
+
+    status_bar
+}
+
+pub fn render(
+    frame: &mut Frame,
+    sys: &info::SystemInfo,
+    state: &AppState,
+    network_order: &[String],
+) {
+    let main_layout = Layout::new(
+        Direction::Vertical,
+        [
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ],
+    )
+    .split(frame.size());
+    frame.render_widget(
+        Block::new()
+            .borders(Borders::TOP)
+            .title(format!("{} {}", sys.spec.os, sys.spec.kernel))
+            .title(health_title(sys))
+            .bold(),
+        main_layout[0],
+    );
+    let rows = state.process_rows(sys);
+
+    frame.render_widget(status_bar(sys, state, &rows), main_layout[2]);
+
+    if let (Some(baseline), Some(report)) = (&state.baseline, &state.baseline_report) {
+        if state.show_baseline {
+            state.transient.layout_rects.set([Rect::default(); 4]);
+            render_baseline(frame, baseline, report, state, main_layout[1]);
+            return;
+        }
+    }
+
+    let inner_layout = Layout::new(
+        Direction::Horizontal,
+        [Constraint::Percentage(50), Constraint::Percentage(50)],
+    )
+    .split(main_layout[1]);
+
+    let left_layout = Layout::new(
+        Direction::Vertical,
+        [Constraint::Percentage(50), Constraint::Percentage(50)],
+    )
+    .split(inner_layout[0]);
+
+    let right_layout = Layout::new(
+        Direction::Vertical,
+        [Constraint::Percentage(50), Constraint::Percentage(50)],
+    )
+    .split(inner_layout[1]);
+
+    state.transient.layout_rects.set([
+        left_layout[0],
+        left_layout[1],
+        right_layout[0],
+        right_layout[1],
+    ]);
+
+    // now split each column into two rows, giving us a 2x2 grid
+    let cpu_brand = sys.usage.cpu_brand().filter(|b| !b.is_empty());
+
+    frame.render_widget(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(border_type(state, Panel::Cpu))
+            .title(match &cpu_brand {
+                Some(brand) => format!("CPU ({})", brand),
+                None => "CPU".to_string(),
+            })
+            .title(refresh_marker(state, Subsystem::Cpu))
+            .yellow(),
+        left_layout[0],
+    );
+    let pressure = sys.memory_pressure_level();
+
+    // flash the border when the box is about to run out of memory entirely
+    let memory_color = if sys.is_oom_risk() && !blink_on() {
+        state.theme.contrast()
+    } else {
+        pressure_color(pressure)
+    };
+
+    frame.render_widget(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Memory")
+            .title(refresh_marker(state, Subsystem::Memory))
+            .fg(memory_color),
+        left_layout[1],
+    );
+
+    frame.render_widget(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(border_type(state, Panel::Specs))
+            .title("Specs/Network")
+            .title(refresh_marker(state, Subsystem::Networks))
+            .red(),
+        right_layout[0],
+    );
+    frame.render_widget(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(border_type(state, Panel::Processes))
+            .title(format!(
+                "Processes [sort: {}{} (s)] [cpu: {}, {} (a/A)] [threads: {} (H)]",
+                state.sorter.label(),
+                if state.frozen_order.is_some() {
+                    ", frozen"
+                } else {
+                    ""
+                },
+                if state.relative_cpu {
+                    "relative"
+                } else {
+                    "absolute"
+                },
+                state.aggregation.label(),
+                if state.merge_threads {
+                    "merged"
+                } else {
+                    "separate"
+                }
+            ))
+            .title(refresh_marker(state, Subsystem::Processes))
+            .magenta(),
+        right_layout[1],
+    );
+
+    // now we can render the actual data
+
+    // first, create four handles, to each paragraph widget
+    let top_left_inner = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            // steal gets a line of its own where it's known
+            Constraint::Length(if sys.usage.cpu_steal.is_some() { 3 } else { 2 }),
+            Constraint::Min(1),
+        ])
+        .split(left_layout[0]);
+
+    let top_right_inner = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1)])
+        .split(right_layout[0]);
+
+    let top_right_inner_inner = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Min(1),
+            Constraint::Min(1),
+        ])
+        .split(top_right_inner[0]);
+
+    let bottom_left_inner = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(1),
+        ])
+        .split(left_layout[1]);
+
+    let bottom_right_inner = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(right_layout[1]);
+
+    // now we can render the actual data
+
+    let labels = state.labels;
+    let units = state.units;
+
+    // a 0 is a core that doesn't report its clock speed, it'd drag the min and avg down
+    let clock_speeds = sys
+        .usage
+        .cpus
+        .iter()
+        .map(|c| c.clock_speed)
+        .filter(|mhz| *mhz > 0.0)
+        .collect::<Vec<f32>>();
+    let clock_stats = state
+        .clock_stats
+        .iter()
+        .filter_map(|stat| Some((*stat, stat.of(&clock_speeds)?)))
+        .collect::<Vec<(ClockStat, f32)>>();
+
+    let mut formatted_cpu = vec![
+        Line::from(if sys.usage.cpu_measured {
+            labels.field(
+                Label::AverageUsage,
+                labels.percent(sys.usage.average_cpu_usage()),
+            )
+        } else {
+            labels.field(Label::AverageUsage, MEASURING)
+        }),
+        // the spread across the cores, turbo boost and power saving pull them apart
+        Line::from(labels.field(Label::ClockSpeed, labels.frequencies(&clock_stats))),
+    ];
+
+    if let Some(steal) = sys.usage.cpu_steal {
+        let steal_line = Line::from(labels.field(Label::Steal, format!("{:.1}%", steal)));
+
+        // the hypervisor is handing this guest's time to someone else
+        formatted_cpu.push(if steal >= STEAL_WARN_PERCENT {
+            steal_line.style(Style::new().red())
+        } else {
+            steal_line
+        });
+    }
+
+    frame.render_widget(Paragraph::new(formatted_cpu).bold(), top_left_inner[1]);
+
+    render_cpu(
+        frame,
+        state,
+        &sys.usage.per_core_summary(),
+        sys.usage.cpu_measured,
+        cpu_brand.is_some(),
+        top_left_inner[2],
+    );
+
+    let uptime = sys.spec.uptime.parse::<u64>().unwrap_or_default();
+
+    let formatted_arch = if sys.spec.architecture == "unknown" {
+        sys.spec.architecture.clone()
+    } else if sys.spec.is_64bit() {
+        format!("{} (64-bit)", sys.spec.architecture)
+    } else {
+        format!("{} (32-bit)", sys.spec.architecture)
+    };
+
+    // now, we move on to system specs
+    let formatted_specs = [
+        labels.field(Label::Hostname, &sys.spec.hostname),
+        labels.field(Label::Os, &sys.spec.os),
+        labels.field(Label::Kernel, &sys.spec.kernel),
+        labels.field(Label::Arch, formatted_arch),
+        labels.field(Label::Uptime, labels.duration(uptime)),
+        labels.field(
+            Label::Env,
+            sys.spec.environment.as_ref().map_or("unknown", info::Environment::label),
+        ),
+        labels.field(
+            Label::Users,
+            // users where uid > 1000
+            sys.spec
+                .users
+                .iter()
+                .filter(|u| u.uid.parse::<u32>().unwrap_or_default() > 1000)
+                .count(),
+        ),
+        // far more threads than processes can point at something spawning them unchecked
+        format!(
+            "{}{}{}",
+            labels.field(Label::Processes, util::format_count(sys.process_count() as u64)),
+            if labels == LabelMode::Full { ", " } else { " " },
+            labels.field(
+                Label::Threads,
+                sys.total_threads().map_or("unknown".to_string(), util::format_count)
+            )
+        ),
+    ]
+    .join("\n");
+
+    let specs_area = top_right_inner_inner[1];
+    let mut specs = formatted_specs
+        .lines()
+        .map(|line| Line::from(line.to_string()))
+        .collect::<Vec<Line>>();
+
+    // easy to lose track of on a server that's been up for months
+    if sys.spec.pending_reboot {
+        specs.insert(0, Line::from("⚠ Reboot required".yellow()));
+    }
+
+    // a long hostname or kernel wraps rather than running off the edge, and whatever doesn't
+    // fit is scrolled to with up and down while the panel is focused. specs_area runs over
+    // the panel's right border, so that column is left out
+    let specs_width = specs_area.width.saturating_sub(1);
+    let specs = Paragraph::new(specs).bold().wrap(Wrap { trim: false });
+    let specs_lines = specs.line_count(specs_width);
+    let specs_hidden = specs_lines.saturating_sub(specs_area.height as usize);
+    let specs_scroll = state.transient.specs_scroll.min(specs_hidden);
+
+    frame.render_widget(
+        specs.scroll((specs_scroll as u16, 0)),
+        Rect::new(specs_area.x, specs_area.y, specs_width, specs_area.height),
+    );
+
+    if specs_scroll > 0 {
+        frame.render_widget(
+            Paragraph::new(format!("▲ {} more", specs_scroll)).alignment(Alignment::Right),
+            Rect::new(specs_area.x, specs_area.y, specs_width, 1),
+        );
+    }
+
+    if specs_scroll < specs_hidden && specs_area.height > 0 {
+        frame.render_widget(
+            Paragraph::new(format!("▼ {} more", specs_hidden - specs_scroll))
+                .alignment(Alignment::Right),
+            Rect::new(specs_area.x, specs_area.bottom() - 1, specs_width, 1),
+        );
+    }
+
+    let specs_lines = specs_lines as u16;
+
+    // the histogram and who's using the machine share whatever room the specs leave,
+    // after a blank line
+    if specs_area.height >= specs_lines + 5 {
+        let below_specs = Layout::new(
+            Direction::Horizontal,
+            [Constraint::Percentage(50), Constraint::Percentage(50)],
+        )
+        // specs_area runs over the panel's right border too, the table would end on it
+        .split(Rect::new(
+            specs_area.x,
+            specs_area.y + specs_lines + 1,
+            specs_area.width.saturating_sub(1),
+            specs_area.height - specs_lines - 1,
+        ));
+
+        render_age_histogram(frame, sys, &state.age_buckets, below_specs[0]);
+        render_user_breakdown(frame, sys, labels, units, below_specs[1]);
+    }
+
+    // now onto memory
+    frame.render_widget(
+        widgets::UsageBar {
+            label: &format!("({} pressure)", pressure.description()),
+            used: sys.usage.memory,
+            total: sys.usage.total_memory,
+            color: pressure_color(pressure),
+        },
+        bottom_left_inner[1],
+    );
+
+    let disk_used = sys.usage.disks.iter().map(|d| d.used).sum::<u64>();
+    let disk_total = sys.usage.disks.iter().map(|d| d.total).sum::<u64>();
+
+    let available = labels.gigabytes(units, sys.usage.available_memory);
+    let available_line = Line::from(if labels == LabelMode::Full {
+        labels.field(
+            Label::Available,
+            format!("{} (includes reclaimable cache)", available),
+        )
+    } else {
+        labels.field(Label::Available, available)
+    });
+    let available_line = if sys.usage.is_available_memory_low() {
+        available_line.style(Style::new().red())
+    } else {
+        available_line
+    };
+
+    // what the processes hold between them, the rest of what's used is the kernel's
+    let process_memory = info::SystemData::memory_used_by_processes(&sys.processes);
+    let attributed_line = Line::from(labels.field(
+        Label::Processes,
+        format!(
+            "{} ({:.1}%{})",
+            labels.gigabytes(units, process_memory),
+            sys.memory_attribution_percent(),
+            if labels == LabelMode::Full { " attributed" } else { "" }
+        ),
+    ));
+    let unattributed_line = Line::from(
+        labels
+            .field(
+                Label::Unattributed,
+                labels.gigabytes(units, sys.usage.memory.saturating_sub(process_memory)),
+            )
+            .dim(),
+    );
+
+    // free memory on linux sits near zero, filled with cache that's dropped as soon as
+    // something needs the room, so available is the figure worth reading
+    let memory_lines = if labels == LabelMode::Full {
+        let mut lines = vec![
+            Line::from("RAM:"),
+            Line::from(labels.field(Label::Used, labels.gigabytes(units, sys.usage.memory))),
+            available_line,
+            Line::from(labels.field(Label::Total, labels.gigabytes(units, sys.usage.total_memory))),
+            attributed_line,
+            unattributed_line,
+        ];
+
+        for (heading, used, total) in [
+            ("SWAP:", sys.usage.swap, sys.usage.total_swap),
+            ("DISK:", disk_used, disk_total),
+        ] {
+            lines.extend([
+                Line::from(""),
+                Line::from(heading),
+                Line::from(labels.field(Label::Used, labels.gigabytes(units, used))),
+                Line::from(labels.field(Label::Total, labels.gigabytes(units, total))),
+            ]);
+        }
+
+        lines
+    } else {
+        // used out of total on one line, 7.2/15.6G
+        let used_of = |used: u64, total: u64| {
+            format!(
+                "{}/{}",
+                labels.gigabytes(units, used).trim_end_matches(char::is_alphabetic),
+                labels.gigabytes(units, total)
+            )
+        };
+
+        vec![
+            Line::from(format!(
+                "RAM {}",
+                labels.field(Label::Used, used_of(sys.usage.memory, sys.usage.total_memory))
+            )),
+            available_line,
+            attributed_line,
+            Line::from(format!(
+                "Swap {}",
+                labels.field(Label::Used, used_of(sys.usage.swap, sys.usage.total_swap))
+            )),
+            Line::from(format!(
+                "Disk {}",
+                labels.field(Label::Used, used_of(disk_used, disk_total))
+            )),
+        ]
+    };
+
+    let memory_area = bottom_left_inner[2];
+    let memory_height = memory_lines.len() as u16;
+
+    frame.render_widget(Paragraph::new(memory_lines).bold(), memory_area);
+
+    // the disks get whatever's left below after a blank line, if there's room for the title,
+    // the header and a row. memory_area runs over the panel's right and bottom borders
+    if memory_area.height >= memory_height + 5 {
+        render_disks(
+            frame,
+            sys,
+            state,
+            Rect::new(
+                memory_area.x,
+                memory_area.y + memory_height + 1,
+                memory_area.width.saturating_sub(1),
+                memory_area.height - memory_height - 2,
+            ),
+        );
+    }
+
+    // now, network. interfaces that have gone away are skipped
+    let ordered_networks = network_order
+        .iter()
+        .filter_map(|n| sys.usage.networks.iter().find(|x| x.name == *n))
+        .collect::<Vec<&info::Network>>();
+
+    // each interface gets a summary line, a sparkline for each direction and its addresses
+    let network_area = top_right_inner_inner[2];
+
+    // a minimal container can start with none, they're picked up if they appear
+    if ordered_networks.is_empty() && network_area.height > 0 {
+        frame.render_widget(
+            Paragraph::new("no network interfaces".dim()),
+            Rect::new(network_area.x, network_area.y, network_area.width, 1),
+        );
+    }
+
+    // five lines each, the last of them blank, and left and right step through the rest.
+    // network_area runs over the panel's bottom border, so that row is left out
+    let network_rows = network_area.height.saturating_sub(1);
+    let networks_fit = (network_rows as usize + 1) / 5;
+
+    let networks_hidden = ordered_networks.len().saturating_sub(networks_fit);
+    let network_scroll = state.transient.network_scroll.min(networks_hidden);
+
+    state.transient.specs_overflow.set((specs_hidden, networks_hidden));
+
+    // on the bottom border, so it doesn't cost an interface
+    if networks_hidden > 0 && network_area.height > 0 {
+        frame.render_widget(
+            Paragraph::new(format!(
+                "◀ {} · {} ▶ (←/→)",
+                network_scroll,
+                networks_hidden - network_scroll
+            ))
+            .alignment(Alignment::Right)
+            .dim(),
+            Rect::new(
+                network_area.x,
+                network_area.bottom() - 1,
+                network_area.width.saturating_sub(1),
+                1,
+            ),
+        );
+    }
+
+    for (i, network) in ordered_networks.into_iter().skip(network_scroll).enumerate() {
+        let y = network_area.y + i as u16 * 5;
+
+        if i >= networks_fit {
+            break;
+        }
+
+        let line = |offset: u16| Rect::new(network_area.x, y + offset, network_area.width, 1);
+
+        frame.render_widget(
+            Paragraph::new(if labels == LabelMode::Full {
+                format!(
+                    "{} [{}] ({}) {} {} / {} {}",
+                    network.name,
+                    network.kind.label(),
+                    network.mac,
+                    labels.text(Label::Sent),
+                    labels.bytes(units, network.total_sent),
+                    labels.text(Label::Received),
+                    labels.bytes(units, network.total_recv),
+                )
+            } else {
+                format!(
+                    "{} {} {}{} {}{}",
+                    network.name,
+                    network.kind.label(),
+                    labels.text(Label::Sent),
+                    labels.bytes(units, network.total_sent),
+                    labels.text(Label::Received),
+                    labels.bytes(units, network.total_recv),
+                )
+            })
+            .bold(),
+            line(0),
+        );
+
+        let history = state.network_history.get(&network.name);
+
+        render_rate(
+            frame,
+            state,
+            line(1),
+            "↑",
+            network.sent_per_sec,
+            history.map(|h| &h.sent_per_sec),
+            Color::Green,
+        );
+        render_rate(
+            frame,
+            state,
+            line(2),
+            "↓",
+            network.recv_per_sec,
+            history.map(|h| &h.recv_per_sec),
+            Color::Blue,
+        );
+
+        let primary = network.primary_ipv4().map(IpAddr::V4);
+        let addresses = network
+            .ipv4_addrs
+            .iter()
+            .copied()
+            .map(IpAddr::V4)
+            .chain(network.ipv6_addrs.iter().copied().map(IpAddr::V6))
+            .map(|address| {
+                let span = Span::from(format!("{} ", address));
+
+                // a public address on a local interface is unusual enough to stand out
+                let span = match AddressScope::of(address) {
+                    AddressScope::Loopback => span.dim(),
+                    AddressScope::LinkLocal => span.yellow(),
+                    AddressScope::Private => span.fg(state.theme.contrast()),
+                    AddressScope::Public => span.green(),
+                };
+
+                if Some(address) == primary {
+                    span.bold()
+                } else {
+                    span
+                }
+            })
+            .collect::<Vec<Span>>();
+
+        frame.render_widget(Paragraph::new(Line::from(addresses)), line(3));
+    }
+
+    // now for the big one, processes
+    let scroll_offset = render_process_panel(frame, sys, state, &rows, bottom_right_inner[1]);
+
+    // the table's first line is its header
+    scrollbar::render_scrollbar(
+        frame,
+        right_layout[1],
+        scroll_offset,
+        rows.len(),
+        bottom_right_inner[1].height.saturating_sub(1) as usize,
+    );
+
+    if let Some(detail) = &state.detail {
+        detail::render(frame, sys, detail, state.note(&detail.group));
+    }
+
+    if let Some(edit) = &state.ionice_edit {
+        detail::render_ionice(frame, edit);
+    }
+
+    // low mode data is stale by design, so say so
+    if state.performance_mode.skips_expensive() {
+        let area = frame.size();
+        frame.buffer_mut().set_style(area, Style::new().dim());
+    }
+}
+
+// the rows come from AppState::process_rows, already grouped and in order. returns the
+// index of the first row shown
+fn render_process_panel(
+    frame: &mut Frame,
+    sys: &info::SystemInfo,
+    state: &AppState,
+    processes: &[ProcessGroup],
+    area: Rect,
+) -> usize {
+    let columns = state.visible_columns(sys);
+    let editing = state.column_edit.as_ref().map(|edit| edit.selected);
+
+    let arrow = if state.sorter.descending() != state.sort_reversed {
+        "▼"
+    } else {
+        "▲"
+    };
+
+    let header = columns
+        .iter()
+        .map(|&column| {
+            let cell = if column == state.sorter.column() {
+                Cell::from(format!("{} {}", column.header(), arrow))
+            } else {
+                Cell::from(column.header())
+            };
+
+            if editing == Some(column) {
+                cell.reversed()
+            } else {
+                cell
+            }
+        })
+        .collect::<Vec<Cell>>();
+
+    // rows are grouped by name, so the sockets of every member count towards it
+    let mut connection_counts = HashMap::new();
+
+    if state.show_connection_count {
+        for process in &sys.processes {
+            if let Some(count) = state.connection_counts.get(&process.pid) {
+                *connection_counts.entry(process.name.as_str()).or_insert(0) += count;
+            }
+        }
+    }
+
+    let mut rows = vec![];
+
+    // in relative mode each process is shown as its share of the cpu time currently in use,
+    // so the column adds up to ~100% even on a mostly idle machine. that's taken over every
+    // process, whatever the rows are filtered or grouped by
+    let busy_cpu = sys.busy_cpu();
+
+    for (i, row) in processes.iter().enumerate() {
+        let process = &row.process;
+
+        let cpu_usage = if state.relative_cpu && busy_cpu > 0.0 {
+            process.cpu_usage / busy_cpu * 100.0
+        } else {
+            process.cpu_usage
+        };
+
+        let cells = columns.iter().map(|column| match column {
+            // a group has no one pid of its own
+            Column::Pid => Cell::from(match row.pid() {
+                Some(pid) => pid.to_string(),
+                None => format!("{} procs", row.pids.len()),
+            }),
+            // kernel threads are background noise next to what's been started in user space
+            Column::Name if process.is_kernel_thread() => Cell::from(process.name.clone()).dim(),
+            Column::Name if process.exe_deleted => {
+                Cell::from(format!("{} [!]", process.name)).fg(state.theme.highlight())
+            }
+            Column::Name => Cell::from(process.name.clone()),
+            Column::Cpu => Cell::from(if sys.processes_measured {
+                format!("{:.2}%", cpu_usage)
+            } else {
+                MEASURING.to_string()
+            }),
+            // the heavy ones stand out whatever the table is sorted by
+            Column::Memory if state.memory_highlight.is_some_and(|limit| process.ram > limit) => {
+                Cell::from(format!("{:.2} MB", process.ram as f32 / 1024.0 / 1024.0))
+                    .red()
+                    .bold()
+            }
+            Column::Memory => {
+                Cell::from(format!("{:.2} MB", process.ram as f32 / 1024.0 / 1024.0))
+            }
+            Column::Uptime => Cell::from(util::format_duration(process.total_time as u64)),
+            Column::State => {
+                let state_name = process.process_state();
+                let cell = Cell::from(match state.state_display {
+                    Some(StateDisplay::Word) => state_name.word(),
+                    Some(StateDisplay::Raw) => process.state.as_str(),
+                    _ => state_name.code(),
+                });
+
+                // a zombie's parent isn't reaping it, which is worth noticing
+                if state_name == ProcessState::Zombie {
+                    cell.red()
+                } else {
+                    cell
+                }
+            }
+            Column::Nice => {
+                // negative nice means a higher priority than usual
+                let nice_style = match process.nice {
+                    n if n < 0 => Style::new().red().bold(),
+                    // the row's own colour, white would vanish on a light background
+                    0 => Style::new(),
+                    _ => Style::new().dim(),
+                };
+
+                // nice values are only read on linux, everywhere else they'd just be a misleading 0
+                if cfg!(target_os = "linux") {
+                    Cell::from(process.nice.to_string()).style(nice_style)
+                } else {
+                    Cell::from("—")
+                }
+            }
+            Column::Sched => Cell::from(process.sched_policy.unwrap_or("—")),
+            Column::Steal => Cell::from(match process.cpu_steal_ms {
+                Some(ms) if ms < 1000 => format!("{}ms", ms),
+                Some(ms) => util::format_duration(ms / 1000),
+                None => "N/A".to_string(),
+            }),
+            Column::Growth => match process.memory_growth {
+                Some(growth) => {
+                    let cell = Cell::from(format!("{:+.2} MB/min", growth / 1024.0 / 1024.0));
+
+                    if growth >= LEAK_WARN_BYTES_PER_MIN {
+                        cell.red()
+                    } else {
+                        cell
+                    }
+                }
+                None => Cell::from(MEASURING),
+            },
+            // only read on linux
+            Column::Faults => match process.major_fault_rate {
+                Some(rate) if rate >= THRASH_FAULTS_PER_SEC => {
+                    Cell::from(format!("{:.1}", rate)).red().bold()
+                }
+                Some(rate) => Cell::from(format!("{:.1}", rate)),
+                None if cfg!(target_os = "linux") => Cell::from(MEASURING),
+                None => Cell::from("—"),
+            },
+            // sockets are only read on linux
+            Column::Conns => Cell::from(if cfg!(target_os = "linux") {
+                connection_counts
+                    .get(process.name.as_str())
+                    .copied()
+                    .unwrap_or(0)
+                    .to_string()
+            } else {
+                "—".to_string()
+            }),
+            Column::Note => Cell::from(state.note(&row.pids).unwrap_or_default().to_string()).italic(),
+        });
+
+        // shade every other row so a line can be followed across the full width
+        let mut row_style = if i % 2 == 1 {
+            Style::new().bg(state.theme.stripe())
+        } else {
+            Style::new()
+        };
+
+        if state.matches_search(row) {
+            row_style = row_style.fg(state.theme.highlight()).bold();
+        }
+
+        rows.push(Row::new(cells.collect::<Vec<Cell>>()).style(row_style));
+    }
+
+    // each column gets its share of the total width of the ones shown
+    let total_width = columns
+        .iter()
+        .map(|c| state.columns.width(*c) as u32)
+        .sum::<u32>();
+    let widths = columns
+        .iter()
+        .map(|c| Constraint::Ratio(state.columns.width(*c) as u32, total_width.max(1)))
+        .collect::<Vec<Constraint>>();
+
+    let table = Table::new(rows, widths)
+        // a header stays put while the rows scroll underneath it
+        .header(Row::new(header).style(Style::new().on_red()))
+        .highlight_style(Style::new().reversed());
+
+    let mut table_state =
+        TableState::default().with_selected(Some(state.transient.selected_process));
+
+    frame.render_stateful_widget(table, area, &mut table_state);
+
+    // an empty table under its header looks like it's still loading
+    if processes.is_empty() && area.height > 1 {
+        frame.render_widget(
+            Paragraph::new("no processes to show".dim()).alignment(Alignment::Center),
+            Rect::new(area.x, area.y + area.height / 2, area.width, 1),
+        );
+    }
+
+    table_state.offset()
+}
+
+// the scrolling list of cores, `shared_brand` when the brand is already in the title
+fn render_cpu(
+    frame: &mut Frame,
+    state: &AppState,
+    cores: &[CoreSummary],
+    measured: bool,
+    shared_brand: bool,
+    area: Rect,
+) {
+    let core_count = cores.len();
+
+    // the first line stays blank to set the cores apart from the averages
+    let mut visible_cores = area.height.saturating_sub(1) as usize;
+
+    if core_count > visible_cores {
+        // and the last one is needed for the scroll indicator
+        visible_cores = visible_cores.saturating_sub(1);
+    }
+
+    state.transient.cpu_rows_visible.set(visible_cores);
+
+    let core_offset = state
+        .transient.cpu_scroll_offset
+        .min(core_count.saturating_sub(visible_cores));
+    let cores_below = core_count.saturating_sub(core_offset + visible_cores);
+
+    let mut lines = vec![Line::from("")];
+
+    for core in cores.iter().skip(core_offset).take(visible_cores) {
+        let labels = state.labels;
+        let usage = if measured {
+            labels.percent(core.usage)
+        } else {
+            MEASURING.to_string()
+        };
+
+        // whatever the platform doesn't report is left out rather than shown as 0 or ()
+        let mut line = if labels == LabelMode::Full {
+            format!("cpu{} ({})", core.core_id, usage)
+        } else {
+            format!("cpu{} {}", core.core_id, usage)
+        };
+
+        if let Some(mhz) = core.clock_speed {
+            if labels == LabelMode::Full {
+                line.push_str(&format!(" at {}", labels.frequency(mhz)));
+            } else {
+                line.push_str(&format!(" {}", labels.frequency(mhz)));
+            }
+        }
+
+        if let Some(vendor) = core.vendor.as_ref().filter(|_| labels == LabelMode::Full) {
+            line.push_str(&format!(" ({})", vendor));
+        }
+
+        let mut spans = vec![Span::from(line)];
+
+        // a shared brand is already in the title, a mix needs spelling out per core
+        if !shared_brand && !core.brand.is_empty() {
+            spans.push(Span::from(format!(" {}", core.brand)));
+        }
+
+        if let Some(temperature) = core.temperature {
+            spans.push(Span::from(format!(" {:.0}°C", temperature)));
+        }
+
+        if core.steal >= STEAL_WARN_PERCENT {
+            spans.push(format!(" steal {:.1}%", core.steal).red());
+        }
+
+        lines.push(Line::from(spans));
+    }
+
+    frame.render_widget(Paragraph::new(lines), area);
+
+    if core_offset > 0 {
+        frame.render_widget(
+            Paragraph::new(format!("▲ {} more", core_offset)).alignment(Alignment::Right),
+            Rect::new(area.x, area.y, area.width, 1),
+        );
+    }
+
+    if cores_below > 0 && area.height > 0 {
+        frame.render_widget(
+            Paragraph::new(format!("▼ {} more", cores_below)).alignment(Alignment::Right),
+            Rect::new(area.x, area.bottom() - 1, area.width, 1),
+        );
+    }
+}
+
+// 90s, 1m, 1h or 1d, whichever whole unit fits
+fn bucket_label(bound: Duration) -> String {
+    let secs = bound.as_secs();
+
+    match secs {
+        0 => format!("{}ms", bound.as_millis()),
+        _ if secs.is_multiple_of(86400) => format!("{}d", secs / 86400),
+        _ if secs.is_multiple_of(3600) => format!("{}h", secs / 3600),
+        _ if secs.is_multiple_of(60) => format!("{}m", secs / 60),
+        _ => format!("{}s", secs),
+    }
+}
+
+// lots of very young processes means something is churning through them
+fn render_age_histogram(
+    frame: &mut Frame,
+    sys: &info::SystemInfo,
+    buckets: &[Duration],
+    area: Rect,
+) {
+    let counts = sys.process_age_histogram(buckets);
+
+    let mut labels = buckets
+        .iter()
+        .map(|b| format!("<{}", bucket_label(*b)))
+        .collect::<Vec<String>>();
+    labels.push(match buckets.last() {
+        Some(last) => format!(">{}", bucket_label(*last)),
+        None => "all".to_string(),
+    });
+
+    let data = labels
+        .iter()
+        .zip(counts)
+        .map(|(label, count)| (label.as_str(), count))
+        .collect::<Vec<(&str, u64)>>();
+
+    let bar_width = (area.width / data.len() as u16)
+        .saturating_sub(1)
+        .clamp(1, 10);
+
+    frame.render_widget(
+        BarChart::default()
+            .block(Block::new().title("Process age"))
+            .data(&data)
+            .bar_width(bar_width)
+            .bar_gap(1)
+            .bar_style(Style::new().cyan())
+            .value_style(Style::new().black().on_cyan()),
+        area,
+    );
+}
+
+// cpu and memory per process owner, pidwatch's own user stands out and whoever doesn't
+// fit is summed into a last row
+fn render_user_breakdown(
+    frame: &mut Frame,
+    sys: &info::SystemInfo,
+    labels: LabelMode,
+    units: SizeUnit,
+    area: Rect,
+) {
+    let mut users = sys.user_breakdown();
+    let current_uid = sys.current_uid();
+
+    // the title and the header take a line each
+    let fits = area.height.saturating_sub(2) as usize;
+
+    if users.len() > fits && fits > 0 {
+        let rest = users.split_off(fits - 1);
+
+        users.push(UserUsage {
+            name: format!("{} others", rest.len()),
+            uid: None,
+            cpu_usage: rest.iter().map(|u| u.cpu_usage).sum(),
+            ram: rest.iter().map(|u| u.ram).sum(),
+            process_count: rest.iter().map(|u| u.process_count).sum(),
+        });
+    }
+
+    let rows = users
+        .iter()
+        .map(|user| {
+            // "alice (zsh)", a service account's shell is usually nologin
+            let name = match sys.user(user.uid.as_deref()).and_then(info::User::shell_name) {
+                Some(shell) => format!("{} ({})", user.name, shell),
+                None => user.name.clone(),
+            };
+
+            let row = Row::new(vec![
+                name,
+                if sys.processes_measured {
+                    format!("{:.1}%", user.cpu_usage)
+                } else {
+                    MEASURING.to_string()
+                },
+                labels.bytes(units, user.ram),
+                user.process_count.to_string(),
+            ]);
+
+            if user.uid.is_some() && user.uid.as_deref() == current_uid {
+                row.cyan().bold()
+            } else {
+                row
+            }
+        })
+        .collect::<Vec<Row>>();
+
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Min(8),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(5),
+            ],
+        )
+        .header(Row::new(["User", "CPU", "RAM", "Procs"]).bold())
+        .block(Block::new().title("Users")),
+        area,
+    );
+}
+
+fn render_disks(frame: &mut Frame, sys: &info::SystemInfo, state: &AppState, area: Rect) {
+    let mut disks = sys.usage.disks.iter().collect::<Vec<&info::Disk>>();
+    sort::sort_disks(&mut disks, state.disk_sorter);
+
+    let empty = disks.is_empty();
+    let rows = disks
+        .iter()
+        .map(|disk| {
+            let row = Row::new(vec![
+                disk.mount.clone(),
+                format!("{:.1}%", disk.percent),
+                state.labels.bytes(state.units, disk.free),
+                state.labels.bytes(state.units, disk.total),
+                disk.inode_percent().map_or("-".to_string(), |p| format!("{:.1}%", p)),
+                format!("{}/s", state.labels.bytes(state.units, disk.read_per_sec)),
+                format!("{}/s", state.labels.bytes(state.units, disk.write_per_sec)),
+            ]);
+
+            if disk.is_nearly_full() {
+                row.red()
+            } else {
+                row
+            }
+        })
+        .collect::<Vec<Row>>();
+
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Min(8),
+                Constraint::Length(6),
+                Constraint::Length(9),
+                Constraint::Length(9),
+                Constraint::Length(6),
+                Constraint::Length(10),
+                Constraint::Length(10),
+            ],
+        )
+        .header(Row::new(["Mount", "Used", "Free", "Size", "Inodes", "Read", "Write"]).bold())
+        .block(
+            Block::new()
+                .title(format!(
+                    "Disks [sorted by {} {}] (d)",
+                    state.disk_sorter.label(),
+                    if state.disk_sorter.descending() {
+                        "↓"
+                    } else {
+                        "↑"
+                    }
+                ))
+                .title(refresh_marker(state, Subsystem::Disks)),
+        ),
+        area,
+    );
+
+    // under the title and the header
+    if empty && area.height > 2 {
+        frame.render_widget(
+            Paragraph::new("no disks".dim()),
+            Rect::new(area.x, area.y + 2, area.width, 1),
+        );
+    }
+
+    let Some(highlighted) = state.disk_sort_menu else {
+        return;
+    };
+
+    // a small menu over the table's right edge, clipped to it if the table is short
+    let menu_width = 14.min(area.width);
+    let menu = Rect::new(
+        area.right() - menu_width,
+        area.y,
+        menu_width,
+        (sort::DiskSorter::ALL.len() as u16 + 2).min(area.height),
+    );
+
+    let lines = sort::DiskSorter::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, sorter)| {
+            let line = Line::from(sorter.label());
+
+            if i == highlighted {
+                line.style(Style::new().reversed())
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<Line>>();
+
+    frame.render_widget(Clear, menu);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title("sort by")),
+        menu,
+    );
+}
+
+// a rect of the given percentages of `area`, centred in it
+fn centered(area: Rect, width_percent: u16, height_percent: u16) -> Rect {
+    let width = area.width * width_percent / 100;
+    let height = area.height * height_percent / 100;
+
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+fn signed_bytes(bytes: i64) -> String {
+    let sign = if bytes < 0 { "-" } else { "+" };
+    format!("{}{}", sign, util::format_bytes(bytes.unsigned_abs()))
+}
+
+// a sparkline as text, for graphs inside a paragraph, scaled to its own peak like render_rate
+fn text_sparkline(history: &RingBuffer<u64>) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let peak = history.iter().copied().max().unwrap_or(0).max(1);
+
+    history
+        .iter()
+        .map(|value| BARS[(value * (BARS.len() as u64 - 1) / peak) as usize])
+        .collect()
+}
+
+fn render_baseline(
+    frame: &mut Frame,
+    baseline: &info::SystemInfo,
+    report: &info::BaselineReport,
+    state: &AppState,
+    area: Rect,
+) {
+    let baseline_history = &state.baseline_history;
+
+    let mut lines = vec![
+        Line::from(format!("CPU {:+.1}% vs baseline", report.cpu_delta)),
+        Line::from(format!(
+            "Memory {} vs baseline",
+            signed_bytes(report.memory_delta)
+        )),
+        Line::from(format!(
+            "Swap {} vs baseline",
+            signed_bytes(report.swap_delta)
+        )),
+        Line::from(format!(
+            "Disk used {} vs baseline",
+            signed_bytes(report.disk_used_delta)
+        )),
+        Line::from(format!(
+            "Network since baseline: {} sent, {} received",
+            util::format_bytes(report.network_sent),
+            util::format_bytes(report.network_recv)
+        )),
+        Line::from(format!(
+            "Processes {:+} vs baseline",
+            report.process_count_delta
+        )),
+        Line::from(""),
+        Line::from(
+            format!(
+                "New since baseline: {} processes",
+                report.new_processes.len()
+            )
+            .bold(),
+        ),
+    ];
+
+    for (pid, name) in &report.new_processes {
+        lines.push(Line::from(format!("  {} ({})", name, pid).green()));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(
+        format!(
+            "Exited since baseline: {} processes",
+            report.exited_processes.len()
+        )
+        .bold(),
+    ));
+
+    for (pid, name) in &report.exited_processes {
+        lines.push(Line::from(format!("  {} ({})", name, pid).red()));
+    }
+
+    if !report.memory_growth.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from("Grew the most".bold()));
+
+        for (pid, name, growth) in &report.memory_growth {
+            lines.push(Line::from(format!(
+                "  {} ({}) {}",
+                name,
+                pid,
+                signed_bytes(*growth)
+            )));
+        }
+    }
+
+    // the traffic leading up to the baseline, sent and then received
+    let graphs = baseline
+        .usage
+        .networks
+        .iter()
+        .filter_map(|n| Some((&n.name, baseline_history.get(&n.name)?)))
+        .filter(|(_, history)| history.sent_per_sec.len() > 0)
+        .collect::<Vec<_>>();
+
+    if !graphs.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from("Network before the baseline".bold()));
+
+        for (name, history) in graphs {
+            lines.push(Line::from(format!(
+                "  {} ↑ {} ↓ {}",
+                name,
+                text_sparkline(&history.sent_per_sec),
+                text_sparkline(&history.recv_per_sec)
+            )));
+        }
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(match &state.baseline_name {
+                    Some(name) => format!(
+                        "Comparison with baseline '{}' (B to go back, b to clear)",
+                        name
+                    ),
+                    None => "Baseline comparison (B to go back, b to clear)".to_string(),
+                })
+                .cyan(),
+        ),
+        area,
+    );
+}
+
+pub fn render_too_small(frame: &mut Frame) {
+    let area = frame.size();
+
+    frame.render_widget(
+        Paragraph::new(format!(
+            "Terminal too small (need {}×{}, got {}×{})",
+            MIN_WIDTH, MIN_HEIGHT, area.width, area.height
+        ))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .red(),
+        // the middle line
+        Rect::new(area.x, area.y + area.height / 2, area.width, 1.min(area.height)),
+    );
+}
+
+pub fn render_error(frame: &mut Frame, message: &str) {
+    frame.render_widget(
+        Paragraph::new(format!(
+            "pidwatch hit an error while rendering:\n\n{}\n\nPress Q to quit",
+            message
+        ))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(Block::default().borders(Borders::ALL).title("Error").red()),
+        frame.size(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn every_panel_gets_an_area() {
+        let sys = info::SystemInfo::new();
+        let state = AppState::new();
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|frame| render(frame, &sys, &state, &[])).unwrap();
+
+        for rect in state.transient.layout_rects.get() {
+            assert!(rect.area() > 0, "{:?}", rect);
+        }
+    }
+}
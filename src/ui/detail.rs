@@ -0,0 +1,440 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    prelude::{Frame, Line, Stylize},
+    style::Style,
+    widgets::{Block, Borders, Clear, Paragraph, Row, Table, Tabs, Wrap},
+};
+
+use crate::{
+    app::{DetailTab, IoniceEdit, ProcessDetail},
+    connections::Connection,
+    info::{self, ProcessData},
+    procfs::{IoClass, IoPriority, IO_LEVEL_MAX},
+    util,
+};
+
+use super::{centered, widgets::TwoColumnText};
+
+// open fds past this share of the soft limit are shown in red
+const FD_WARN_FRACTION: f64 = 0.8;
+
+// "unknown" for whatever /proc wouldn't give up
+fn or_unknown<T: ToString>(value: Option<T>) -> String {
+    value.map_or("unknown".to_string(), |v| v.to_string())
+}
+
+// "1024 (hard: 4096)", or just the one figure when they're the same
+fn limit(limit: Option<(u64, u64)>, format: impl Fn(u64) -> String) -> String {
+    let format = |value: u64| {
+        if value == u64::MAX {
+            "unlimited".to_string()
+        } else {
+            format(value)
+        }
+    };
+
+    match limit {
+        Some((soft, hard)) if soft == hard => format(soft),
+        Some((soft, hard)) => format!("{} (hard: {})", format(soft), format(hard)),
+        None => "unknown".to_string(),
+    }
+}
+
+fn field(label: &str, value: impl ToString) -> Line<'static> {
+    Line::from(format!("{}: {}", label, value.to_string()))
+}
+
+pub fn render(
+    frame: &mut Frame,
+    sys: &info::SystemInfo,
+    detail: &ProcessDetail,
+    note: Option<&str>,
+) {
+    let area = centered(frame.size(), 70, 70);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            "Process {} (←/→ tabs, i io priority, esc to close)",
+            detail.pid
+        ))
+        .green();
+    let inner = block.inner(area);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let layout = Layout::new(
+        Direction::Vertical,
+        [Constraint::Length(2), Constraint::Min(0)],
+    )
+    .split(inner);
+
+    frame.render_widget(
+        Tabs::new(DetailTab::ALL.map(DetailTab::label).to_vec())
+            .select(detail.tab.index())
+            .highlight_style(Style::new().bold().underlined()),
+        layout[0],
+    );
+
+    let Some(process) = sys.processes.iter().find(|p| p.pid == detail.pid) else {
+        frame.render_widget(Paragraph::new("this process has exited".red()), layout[1]);
+        return;
+    };
+
+    match detail.tab {
+        DetailTab::Info => render_info(frame, sys, process, detail, note, layout[1]),
+        DetailTab::Memory => render_memory(frame, process, detail, layout[1]),
+        DetailTab::Io => render_io(frame, detail, layout[1]),
+        DetailTab::Network => render_network(frame, detail, layout[1]),
+        DetailTab::Environment => render_environment(frame, detail, layout[1]),
+        DetailTab::Syscalls => render_syscalls(frame, detail, layout[1]),
+    }
+}
+
+// a small box over the detail view, the class picked with ←/→ and the level with ↑/↓
+pub fn render_ionice(frame: &mut Frame, edit: &IoniceEdit) {
+    let area = centered(frame.size(), 40, 30);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("I/O priority of {}", edit.pid))
+        .yellow();
+    let inner = block.inner(area);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let priority = edit.priority;
+    let level = if priority.class.has_level() {
+        format!("{} (0 highest, {} lowest)", priority.level, IO_LEVEL_MAX)
+    } else {
+        "none for this class".to_string()
+    };
+
+    let class = format!("◀ {} ▶", priority.class.label());
+    let line = |i: u16| Rect::new(inner.x, inner.y + i, inner.width, 1).intersection(inner);
+
+    frame.render_widget(TwoColumnText { left: "Class", right: &class }, line(0));
+    frame.render_widget(TwoColumnText { left: "Level", right: &level }, line(1));
+    frame.render_widget(
+        Paragraph::new("←/→ class, ↑/↓ level, enter to apply, esc to cancel".dim())
+            .wrap(Wrap { trim: true }),
+        Rect::new(inner.x, inner.y + 3, inner.width, inner.height.saturating_sub(3))
+            .intersection(inner),
+    );
+}
+
+// "4521 (bash)", named from the current process list
+fn parent(sys: &info::SystemInfo, process: &ProcessData) -> String {
+    let Some(pid) = process.parent_pid else {
+        return "none".to_string();
+    };
+
+    match sys.processes.iter().find(|p| p.pid == pid) {
+        Some(parent) => format!("{} ({})", pid, parent.name),
+        // pid 1 is left out of the list inside some containers
+        None if pid == 1 => "1 (init)".to_string(),
+        None => format!("{} (gone)", pid),
+    }
+}
+
+// "alice (home /home/alice)", the uid where it doesn't resolve to a name
+fn owner(sys: &info::SystemInfo, process: &ProcessData) -> String {
+    let Some(uid) = process.uid.as_deref() else {
+        return "unknown".to_string();
+    };
+
+    match sys.user(Some(uid)) {
+        Some(user) => match &user.home_dir {
+            Some(home) => format!("{} (home {})", user.name, home.display()),
+            None => user.name.clone(),
+        },
+        None => uid.to_string(),
+    }
+}
+
+fn render_info(
+    frame: &mut Frame,
+    sys: &info::SystemInfo,
+    process: &ProcessData,
+    detail: &ProcessDetail,
+    note: Option<&str>,
+    area: Rect,
+) {
+    let mut lines = vec![];
+
+    // an upgrade replaced it on disk, it keeps running the old code until restarted
+    if process.exe_deleted {
+        lines.extend([
+            Line::from(
+                "⚠ Executable on disk has been deleted. Service restart recommended.".red(),
+            ),
+            Line::from(""),
+        ]);
+    }
+
+    if let Some(note) = note {
+        lines.extend([
+            field("Note", note).style(Style::new().italic()),
+            Line::from(""),
+        ]);
+    }
+
+    lines.extend([
+        field("PID", process.pid),
+        field("Name", &process.name),
+        field("State", &process.state),
+        field("Parent", parent(sys, process)),
+        field("User", owner(sys, process)),
+        field("Exe", &process.exe),
+        field("Cwd", process.cwd.as_deref().unwrap_or("unknown")),
+        field("Nice", process.nice),
+        field(
+            "I/O priority",
+            or_unknown(detail.extras.io_priority.map(|p| match p.class {
+                // the kernel derives it from the nice value
+                IoClass::None => format!("none ({})", IoPriority::from_nice(process.nice).label()),
+                _ => p.label(),
+            })),
+        ),
+        field("Threads", or_unknown(detail.extras.threads)),
+        field("CPU", format!("{:.2}%", process.cpu_usage)),
+    ]);
+
+    let limits = &detail.extras.limits;
+
+    // running out of fds fails in ways that rarely mention the limit
+    let open_files = field("Open files", limit(limits.open_files, |n| n.to_string()));
+    let fds_near_limit = detail
+        .extras
+        .open_fds
+        .zip(limits.open_files)
+        .is_some_and(|(open, (soft, _))| open as f64 > soft as f64 * FD_WARN_FRACTION);
+
+    lines.extend([
+        Line::from(""),
+        if fds_near_limit {
+            open_files.style(Style::new().red())
+        } else {
+            open_files
+        },
+        field("Stack size", limit(limits.stack_size, util::format_bytes)),
+        field(
+            "Virtual memory",
+            limit(limits.virtual_memory, util::format_bytes),
+        ),
+        field("CPU time", limit(limits.cpu_time, util::format_duration)),
+    ]);
+
+    // the row it came from stood for several processes, this is only one of them
+    if detail.group.len() > 1 {
+        lines.push(Line::from(""));
+        lines.push(field(
+            "Group",
+            format!(
+                "the busiest of {} named {}: {}",
+                detail.group.len(),
+                process.name,
+                detail
+                    .group
+                    .iter()
+                    .map(u32::to_string)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        ));
+    }
+
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+fn render_memory(frame: &mut Frame, process: &ProcessData, detail: &ProcessDetail, area: Rect) {
+    let extras = &detail.extras;
+
+    let lines = vec![
+        field("RAM", util::format_bytes(process.ram)),
+        field("Virtual", util::format_bytes(process.virtual_memory)),
+        field("Swap", or_unknown(extras.swap.map(util::format_bytes))),
+        field("Memory maps", or_unknown(extras.memory_maps)),
+        // from 0 to 1000, the highest is killed first when memory runs out
+        field(
+            "OOM score",
+            or_unknown(extras.oom_score.map(|s| format!("{} of 1000", s))),
+        ),
+    ];
+
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+fn render_io(frame: &mut Frame, detail: &ProcessDetail, area: Rect) {
+    let extras = &detail.extras;
+
+    let lines = vec![
+        field("Read", or_unknown(extras.read_bytes.map(util::format_bytes))),
+        field(
+            "Written",
+            or_unknown(extras.write_bytes.map(util::format_bytes)),
+        ),
+        field("Open fds", or_unknown(extras.open_fds)),
+        field("Memory-mapped files", or_unknown(extras.mapped_files)),
+    ];
+
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+fn render_network(frame: &mut Frame, detail: &ProcessDetail, area: Rect) {
+    let listening = detail.connections.iter().filter(|c| c.is_listening()).count();
+
+    let layout = Layout::new(
+        Direction::Vertical,
+        [Constraint::Length(2), Constraint::Min(0)],
+    )
+    .split(area);
+
+    frame.render_widget(
+        Paragraph::new(format!(
+            "Sockets: {} listening, {} other",
+            listening,
+            detail.connections.len() - listening
+        )),
+        layout[0],
+    );
+
+    render_connections(frame, &detail.connections, layout[1]);
+}
+
+// one variable a line, cut to the width, with a count of what didn't fit
+fn render_environment(frame: &mut Frame, detail: &ProcessDetail, area: Rect) {
+    let Some(environment) = &detail.extras.environment else {
+        frame.render_widget(
+            Paragraph::new("not readable, it needs the owner's or root's access".dim()),
+            area,
+        );
+        return;
+    };
+
+    if environment.is_empty() {
+        frame.render_widget(Paragraph::new("no environment variables".dim()), area);
+        return;
+    }
+
+    let width = area.width as usize;
+    let mut shown = area.height as usize;
+
+    if environment.len() > shown {
+        // the last line is needed for the count
+        shown = shown.saturating_sub(1);
+    }
+
+    let mut lines = environment
+        .iter()
+        .take(shown)
+        .map(|(key, value)| {
+            let var = format!("{}={}", key, value);
+
+            if var.chars().count() > width {
+                let cut = var.chars().take(width.saturating_sub(1)).collect::<String>();
+                Line::from(format!("{}…", cut))
+            } else {
+                Line::from(var)
+            }
+        })
+        .collect::<Vec<Line>>();
+
+    if environment.len() > shown {
+        lines.push(Line::from(format!("{} more", environment.len() - shown).dim()));
+    }
+
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+// a snapshot of what it's doing now, following along is left to strace in another terminal
+fn render_syscalls(frame: &mut Frame, detail: &ProcessDetail, area: Rect) {
+    if !cfg!(target_os = "linux") {
+        frame.render_widget(Paragraph::new("not available on this platform".dim()), area);
+        return;
+    }
+
+    let extras = &detail.extras;
+
+    let current = match extras.syscall.as_deref() {
+        Some("running") => "running".to_string(),
+        // -1 is in user space or not in a syscall
+        Some("-1") => "not in a syscall".to_string(),
+        Some(number) => format!("blocked in syscall {}", number),
+        None => "unknown".to_string(),
+    };
+
+    let mut lines = vec![
+        field("Now", current),
+        field("Waiting in", or_unknown(extras.wait_channel.as_ref())),
+        Line::from(""),
+    ];
+
+    lines.push(match &detail.strace {
+        Some(strace) => Line::from(format!(
+            "{} -p {} follows its syscalls live",
+            strace.display(),
+            detail.pid
+        )),
+        None => Line::from("install strace to follow its syscalls live".dim()),
+    });
+
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+// listening sockets first, they're usually what's being looked for
+fn render_connections(frame: &mut Frame, connections: &[Connection], area: Rect) {
+    // connections are only read on linux
+    if !cfg!(target_os = "linux") {
+        frame.render_widget(Paragraph::new("not available on this platform".dim()), area);
+        return;
+    }
+
+    let mut sorted = connections.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|c| !c.is_listening());
+
+    let rows = sorted
+        .into_iter()
+        .map(|c| {
+            let row = Row::new(vec![
+                c.protocol.to_string(),
+                c.local.to_string(),
+                if c.is_listening() {
+                    String::new()
+                } else {
+                    c.remote.to_string()
+                },
+                c.state.to_string(),
+            ]);
+
+            if c.is_listening() {
+                row.bold()
+            } else {
+                row
+            }
+        })
+        .collect::<Vec<Row>>();
+
+    let empty = rows.is_empty();
+
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Length(6),
+                Constraint::Percentage(40),
+                Constraint::Percentage(40),
+                Constraint::Min(11),
+            ],
+        )
+        .header(Row::new(["Proto", "Local", "Remote", "State"]).style(Style::new().on_red())),
+        area,
+    );
+
+    if empty && area.height > 1 {
+        frame.render_widget(
+            Paragraph::new("no open sockets".dim()),
+            Rect::new(area.x, area.y + 1, area.width, 1),
+        );
+    }
+}
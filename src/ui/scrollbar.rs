@@ -0,0 +1,33 @@
+use ratatui::{layout::Rect, prelude::Frame};
+
+// a thumb drawn over the right border of `area`, sized to the share of the list that's
+// visible and placed by how far it's scrolled. nothing is drawn while the whole list fits
+pub fn render_scrollbar(
+    frame: &mut Frame,
+    area: Rect,
+    scroll_offset: usize,
+    total: usize,
+    visible: usize,
+) {
+    // the border's own corners are left alone
+    let track = area.height.saturating_sub(2) as usize;
+
+    if total <= visible || track == 0 || area.width == 0 {
+        return;
+    }
+
+    let thumb_len = (visible * track / total).max(1);
+
+    // rounding down would leave it short of the bottom at the end of the list
+    let thumb_pos = if scroll_offset + visible >= total {
+        track.saturating_sub(thumb_len)
+    } else {
+        scroll_offset * track / total.max(1)
+    };
+    let x = area.right() - 1;
+    let buffer = frame.buffer_mut();
+
+    for i in thumb_pos..(thumb_pos + thumb_len).min(track) {
+        buffer.get_mut(x, area.y + 1 + i as u16).set_symbol("█");
+    }
+}
@@ -0,0 +1,173 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Style, Stylize},
+    widgets::{Gauge, Paragraph, Sparkline, Widget},
+};
+
+// a gauge filled to used/total, labelled "42.0% <label>"
+pub struct UsageBar<'a> {
+    pub label: &'a str,
+    pub used: u64,
+    pub total: u64,
+    pub color: Color,
+}
+
+impl Widget for UsageBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+
+        let ratio = if self.total == 0 {
+            0.0
+        } else {
+            self.used as f64 / self.total as f64
+        };
+
+        let label = format!("{:.1}% {}", ratio * 100.0, self.label);
+
+        Gauge::default()
+            .gauge_style(Style::new().fg(self.color))
+            .ratio(ratio.clamp(0.0, 1.0))
+            .label(label.trim_end().to_string())
+            .render(area, buf);
+    }
+}
+
+// a label in the first `label_width` columns and a sparkline in the rest. the label width
+// is fixed so the sparklines of several lines start in the same column
+pub struct SparklineWidget<'a> {
+    pub label: &'a str,
+    pub data: &'a [u64],
+    // the value drawn at full height, 0 to scale to the data's own peak so a quiet series
+    // still shows its shape
+    pub max: u64,
+    pub label_width: u16,
+    pub color: Color,
+}
+
+impl Widget for SparklineWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+
+        let split = Layout::new(
+            Direction::Horizontal,
+            [Constraint::Length(self.label_width), Constraint::Min(0)],
+        )
+        .split(area);
+
+        let max = if self.max == 0 {
+            self.data.iter().copied().max().unwrap_or(0)
+        } else {
+            self.max
+        };
+
+        Paragraph::new(self.label).fg(self.color).render(split[0], buf);
+        Sparkline::default()
+            .data(self.data)
+            .max(max.max(1))
+            .style(Style::new().fg(self.color))
+            .render(split[1], buf);
+    }
+}
+
+// "key     value" on one line, the value against the right edge
+pub struct TwoColumnText<'a> {
+    pub left: &'a str,
+    pub right: &'a str,
+}
+
+impl Widget for TwoColumnText<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+
+        // the value wins where both don't fit, it's what's being looked for
+        Paragraph::new(self.left).render(area, buf);
+        Paragraph::new(self.right)
+            .alignment(Alignment::Right)
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ratatui::{backend::TestBackend, Terminal};
+
+    // each row of the screen as text, after drawing `widget` into `area`
+    fn draw(widget: impl Widget, area: Rect) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(12, 1)).unwrap();
+        terminal.draw(|frame| frame.render_widget(widget, area)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+
+        (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer.get(x, y).symbol()).collect())
+            .collect()
+    }
+
+    const FULL: Rect = Rect {
+        x: 0,
+        y: 0,
+        width: 12,
+        height: 1,
+    };
+    const EMPTY: Rect = Rect {
+        x: 0,
+        y: 0,
+        width: 0,
+        height: 0,
+    };
+    const BLANK: &str = "            ";
+
+    fn usage_bar() -> UsageBar<'static> {
+        UsageBar {
+            label: "ram",
+            used: 1,
+            total: 4,
+            color: Color::Green,
+        }
+    }
+
+    #[test]
+    fn usage_bar_shows_the_percentage() {
+        // a quarter of the width filled, under the centred label
+        assert_eq!(draw(usage_bar(), FULL), ["█25.0% ram  "]);
+        assert_eq!(draw(usage_bar(), EMPTY), [BLANK]);
+    }
+
+    fn sparkline() -> SparklineWidget<'static> {
+        SparklineWidget {
+            label: "net",
+            data: &[0, 4, 8],
+            max: 0,
+            label_width: 5,
+            color: Color::Cyan,
+        }
+    }
+
+    #[test]
+    fn sparkline_starts_after_the_label() {
+        assert_eq!(draw(sparkline(), FULL), ["net   ▄█    "]);
+        assert_eq!(draw(sparkline(), EMPTY), [BLANK]);
+    }
+
+    fn two_columns() -> TwoColumnText<'static> {
+        TwoColumnText {
+            left: "pid",
+            right: "1234",
+        }
+    }
+
+    #[test]
+    fn two_column_text_puts_the_value_on_the_right() {
+        assert_eq!(draw(two_columns(), FULL), ["pid     1234"]);
+        assert_eq!(draw(two_columns(), EMPTY), [BLANK]);
+    }
+}
@@ -0,0 +1,152 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+// how often the quit flag is checked while waiting for the next sample
+const QUIT_POLL: Duration = Duration::from_millis(100);
+
+// sleeps for `interval` in short steps, false as soon as the quit flag is set
+pub fn wait(interval: Duration, quit: &AtomicBool) -> bool {
+    let start = Instant::now();
+
+    while start.elapsed() < interval {
+        if quit.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        // the interval may have passed since the check above
+        thread::sleep(QUIT_POLL.min(interval.saturating_sub(start.elapsed())));
+    }
+
+    !quit.load(Ordering::Relaxed)
+}
+
+// e.g. 2024-03-09T14:02:11Z, without pulling in a date crate
+pub fn format_timestamp_utc(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let (days, secs_of_day) = (secs / 86400, secs % 86400);
+
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60
+    )
+}
+
+// 1023 B, 1.2 KB, 4.5 MB, ...
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+// 1,842 and 1,000,000
+pub fn format_count(count: u64) -> String {
+    let digits = count.to_string();
+    let mut formatted = String::new();
+
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+
+        formatted.push(digit);
+    }
+
+    formatted
+}
+
+// 42s, 4m 10s, 5h 12m or 3d 14h, only the two largest units
+pub fn format_duration(secs: u64) -> String {
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else if secs < 86400 {
+        format!("{}h {}m", secs / 3600, secs % 3600 / 60)
+    } else {
+        format!("{}d {}h", secs / 86400, secs % 86400 / 3600)
+    }
+}
+
+// format_duration without the spaces, 3d14h, for tight spots like the status bar
+pub fn format_duration_short(secs: u64) -> String {
+    format_duration(secs).replace(' ', "")
+}
+
+// format_bytes with a single letter unit and no space, 1.2K, for compact labels
+pub fn format_bytes_compact(bytes: u64) -> String {
+    let formatted = format_bytes(bytes).replace(' ', "");
+
+    match formatted.strip_suffix('B') {
+        Some(number) if number.ends_with(char::is_alphabetic) => number.to_string(),
+        _ => formatted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_duration_switches_units_at_each_boundary() {
+        assert_eq!(format_duration(0), "0s");
+        assert_eq!(format_duration(59), "59s");
+        assert_eq!(format_duration(60), "1m 0s");
+        assert_eq!(format_duration(3599), "59m 59s");
+        assert_eq!(format_duration(3600), "1h 0m");
+        assert_eq!(format_duration(86399), "23h 59m");
+        assert_eq!(format_duration(86400), "1d 0h");
+    }
+
+    #[test]
+    fn format_duration_short_drops_the_spaces() {
+        assert_eq!(format_duration_short(59), "59s");
+        assert_eq!(format_duration_short(60), "1m0s");
+        assert_eq!(format_duration_short(3599), "59m59s");
+        assert_eq!(format_duration_short(3600), "1h0m");
+        assert_eq!(format_duration_short(86399), "23h59m");
+        assert_eq!(format_duration_short(86400), "1d0h");
+    }
+
+    #[test]
+    fn wait_stops_early_once_asked_to_quit() {
+        let quit = AtomicBool::new(false);
+        assert!(wait(Duration::from_millis(1), &quit));
+        assert!(wait(Duration::ZERO, &quit));
+
+        quit.store(true, Ordering::Relaxed);
+        let start = Instant::now();
+        assert!(!wait(Duration::from_secs(60), &quit));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,164 @@
+use std::{
+    io::{self, Write},
+    sync::atomic::AtomicBool,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    info::{Subsystem, SystemInfo},
+    util,
+};
+
+// what `pidwatch watch` keeps an eye on, each as a percentage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchMetric {
+    Cpu,
+    Memory,
+    Swap,
+    // the fullest disk
+    Disk,
+}
+
+impl WatchMetric {
+    pub const ALL: [WatchMetric; 4] = [
+        WatchMetric::Cpu,
+        WatchMetric::Memory,
+        WatchMetric::Swap,
+        WatchMetric::Disk,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WatchMetric::Cpu => "cpu",
+            WatchMetric::Memory => "memory",
+            WatchMetric::Swap => "swap",
+            WatchMetric::Disk => "disk",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<WatchMetric, String> {
+        WatchMetric::ALL
+            .into_iter()
+            .find(|m| m.label() == value)
+            .ok_or_else(|| format!("expected cpu, memory, swap or disk, got '{}'", value))
+    }
+
+    fn subsystem(self) -> Subsystem {
+        match self {
+            WatchMetric::Cpu => Subsystem::Cpu,
+            WatchMetric::Memory | WatchMetric::Swap => Subsystem::Memory,
+            WatchMetric::Disk => Subsystem::Disks,
+        }
+    }
+
+    // None while it can't be measured yet, cpu usage needs two samples
+    fn value(self, sys: &SystemInfo) -> Option<f64> {
+        let usage = &sys.usage;
+        let percent = |used: u64, total: u64| {
+            if total == 0 {
+                0.0
+            } else {
+                used as f64 / total as f64 * 100.0
+            }
+        };
+
+        match self {
+            WatchMetric::Cpu => usage.cpu_measured.then(|| usage.average_cpu_usage() as f64),
+            WatchMetric::Memory => Some(percent(usage.memory, usage.total_memory)),
+            WatchMetric::Swap => Some(percent(usage.swap, usage.total_swap)),
+            WatchMetric::Disk => Some(
+                usage
+                    .disks
+                    .iter()
+                    .map(|d| d.percent as f64)
+                    .fold(0.0, f64::max),
+            ),
+        }
+    }
+}
+
+// the metric above `threshold` for at least `duration` without a break
+#[derive(Debug, Clone)]
+pub struct WatchCondition {
+    pub metric: WatchMetric,
+    pub threshold: f64,
+    pub duration: Duration,
+}
+
+// how long the condition has held, reset whenever it stops holding
+struct Breach {
+    condition: WatchCondition,
+    // waiting for the metric to drop back below the threshold rather than go above it
+    clear: bool,
+    // with clear, it has to have been above the threshold before dropping counts
+    seen_above: bool,
+    since: Option<Instant>,
+}
+
+impl Breach {
+    // true once the condition has held for its whole duration
+    fn update(&mut self, value: f64, now: Instant) -> bool {
+        let above = value > self.condition.threshold;
+        self.seen_above |= above;
+
+        let holding = if self.clear {
+            self.seen_above && !above
+        } else {
+            above
+        };
+
+        if !holding {
+            self.since = None;
+            return false;
+        }
+
+        let since = *self.since.get_or_insert(now);
+        now.duration_since(since) >= self.condition.duration
+    }
+}
+
+// samples the metric every `interval` until the condition has held for long enough, then
+// prints why and returns true. false when it's stopped by a signal first
+pub fn run(
+    condition: WatchCondition,
+    clear: bool,
+    interval: Duration,
+    quit: &AtomicBool,
+) -> io::Result<bool> {
+    let metric = condition.metric;
+    let mut sys = SystemInfo::new();
+    let mut breach = Breach {
+        condition,
+        clear,
+        seen_above: false,
+        since: None,
+    };
+
+    sys.populate_subsystem(metric.subsystem());
+
+    while util::wait(interval, quit) {
+        sys.populate_subsystem(metric.subsystem());
+
+        let Some(value) = metric.value(&sys) else {
+            continue;
+        };
+
+        if breach.update(value, Instant::now()) {
+            let condition = &breach.condition;
+
+            writeln!(
+                io::stdout(),
+                "{} {} {:.0}% for {}s, now {:.1}%",
+                metric.label(),
+                if clear { "back below" } else { "above" },
+                condition.threshold,
+                condition.duration.as_secs_f64(),
+                value
+            )?;
+
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
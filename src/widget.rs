@@ -0,0 +1,77 @@
+use std::{
+    io::{self, IsTerminal, Write},
+    sync::atomic::AtomicBool,
+    time::Duration,
+};
+
+use crate::{
+    info::{Subsystem, SystemInfo},
+    labels::{LabelMode, SizeUnit},
+    sort::{self, Aggregation, ProcessSorter},
+    util,
+};
+
+// only what the line shows is refreshed, networks and disks are left alone
+const SUBSYSTEMS: [Subsystem; 3] = [Subsystem::Cpu, Subsystem::Memory, Subsystem::Processes];
+
+// "CPU 12.3% RAM 3.2/15.6G top firefox 23.1%", threads merged and same-named processes
+// summed, like the table's top row
+fn summary(sys: &SystemInfo) -> String {
+    let labels = LabelMode::Compact;
+    let usage = &sys.usage;
+
+    let mut line = format!(
+        "CPU {} RAM {}/{}",
+        labels.percent(usage.average_cpu_usage()),
+        labels.gigabytes(SizeUnit::Auto, usage.memory).trim_end_matches('G'),
+        labels.gigabytes(SizeUnit::Auto, usage.total_memory)
+    );
+
+    let processes = sort::merge_threads(&sys.processes);
+    let groups = sort::grouped_processes(&processes, ProcessSorter::ByCpu, Aggregation::Sum);
+
+    if let Some(top) = groups.first().filter(|_| sys.processes_measured) {
+        line.push_str(&format!(
+            " top {} {}",
+            top.process.name,
+            labels.percent(top.process.cpu_usage)
+        ));
+    }
+
+    line
+}
+
+// a line on the normal screen every `interval`, for a tmux status line or a corner of a
+// terminal. on a terminal it's rewritten in place, otherwise each refresh gets its own line
+// so whatever reads it can take the latest
+pub fn run(interval: Duration, quit: &AtomicBool) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    let in_place = out.is_terminal();
+
+    let mut sys = SystemInfo::new();
+
+    for subsystem in SUBSYSTEMS {
+        sys.populate_subsystem(subsystem);
+    }
+
+    // usage is only known from the second sample
+    while util::wait(interval, quit) {
+        for subsystem in SUBSYSTEMS {
+            sys.populate_subsystem(subsystem);
+        }
+
+        if in_place {
+            write!(out, "\r\x1b[K{}", summary(&sys))?;
+        } else {
+            writeln!(out, "{}", summary(&sys))?;
+        }
+
+        out.flush()?;
+    }
+
+    if in_place {
+        writeln!(out)?;
+    }
+
+    Ok(())
+}